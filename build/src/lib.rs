@@ -0,0 +1,187 @@
+use std::path::{Path, PathBuf};
+
+use avenue::{Asset, AssetSet, Error, FileAsset, Process};
+
+type ProcessFn =
+    dyn Fn(FileAsset<'static, String>) -> Result<Vec<u8>, Error> + Send + Sync;
+
+/// Processes a directory of assets from a build script.
+///
+/// Every file in the source directory is matched against the registered
+/// globs in order, and the first matching process chain is used to produce
+/// the output. Files that match no glob are copied as-is. Outputs are written
+/// under the output directory with the same relative paths, and cargo is told
+/// to rerun the build script whenever an input changes.
+///
+/// The source directory is scanned like [`AssetSet::from_dir`], so hidden
+/// files and files ignored by `.gitignore` or `.ignore` files are skipped.
+///
+/// With the `rayon` feature, files are processed in parallel.
+///
+/// ```ignore
+/// use avenue_web::Builder;
+///
+/// avenue_build::Build::new("assets")
+///     .process("*.css", |asset| asset.minify_css().compress_brotli())
+///     .process("*.js", |asset| asset.minify_js())
+///     .run()
+///     .unwrap();
+/// ```
+pub struct Build {
+    src: PathBuf,
+    out: Option<PathBuf>,
+    rules: Vec<(String, Box<ProcessFn>)>,
+}
+
+impl Build {
+    /// Creates a build for the given source directory.
+    ///
+    /// Relative paths are resolved against the current directory, which is
+    /// the crate root when running a build script.
+    pub fn new<P: Into<PathBuf>>(src: P) -> Self {
+        Self {
+            src: src.into(),
+            out: None,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Sets the directory outputs are written to.
+    ///
+    /// Defaults to a directory in `OUT_DIR` with the same name as the source
+    /// directory, so `Build::new("assets")` writes to `$OUT_DIR/assets`.
+    pub fn out_dir<P: Into<PathBuf>>(mut self, out: P) -> Self {
+        self.out = Some(out.into());
+        self
+    }
+
+    /// Processes files matching `glob` with the process chain returned by
+    /// `f`.
+    ///
+    /// Globs are matched against the forward-slash separated path relative to
+    /// the source directory, which is also used as the asset key.
+    pub fn process<F, P>(mut self, glob: &str, f: F) -> Self
+    where
+        F: Fn(FileAsset<'static, String>) -> P + Send + Sync + 'static,
+        P: Process,
+    {
+        let f = move |asset| {
+            f(asset)
+                .process_full()
+                .map(|out| out.as_ref().to_vec())
+                .map_err(Error::other)
+        };
+        self.rules.push((glob.into(), Box::new(f)));
+        self
+    }
+
+    /// Processes every file in the source directory and writes the outputs.
+    pub fn run(self) -> Result<(), Error> {
+        let out = match self.out {
+            Some(out) => out,
+            None => {
+                let out_dir = std::env::var_os("OUT_DIR").ok_or_else(|| {
+                    Error::other(
+                        "`OUT_DIR` is not set, is this a build script?",
+                    )
+                })?;
+                let name = self.src.file_name().ok_or_else(|| {
+                    Error::other(format!(
+                        "`{}` has no directory name",
+                        self.src.display()
+                    ))
+                })?;
+                Path::new(&out_dir).join(name)
+            }
+        };
+
+        let mut rules = Vec::with_capacity(self.rules.len());
+        for (glob, f) in &self.rules {
+            let matcher = globset::Glob::new(glob)
+                .map_err(Error::other)?
+                .compile_matcher();
+            rules.push((matcher, f.as_ref()));
+        }
+
+        println!("cargo:rerun-if-changed={}", self.src.display());
+
+        let mut jobs = Vec::new();
+        for asset in AssetSet::from_dir(&self.src)? {
+            let Asset::File(asset) = asset else {
+                unreachable!("scanned assets should be files");
+            };
+            println!("cargo:rerun-if-changed={}", asset.path.display());
+
+            let f = rules
+                .iter()
+                .find(|(matcher, _)| matcher.is_match(&asset.key))
+                .map(|(_, f)| *f);
+            let dest = out.join(&asset.key);
+            jobs.push((f, asset, dest));
+        }
+
+        avenue::map_batch(jobs, |(f, asset, dest)| {
+            let key = asset.key.clone();
+            build_file(f, asset, &dest).map_err(|err| err.with_key(key))
+        })
+        .into_iter()
+        .collect()
+    }
+}
+
+/// Processes `asset` with `f`, or copies it if there is no matching rule.
+fn build_file(
+    f: Option<&ProcessFn>,
+    asset: FileAsset<'static, String>,
+    dest: &Path,
+) -> Result<(), Error> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    match f {
+        Some(f) => {
+            let contents = f(asset)?;
+            std::fs::write(dest, contents)?;
+        }
+        None => {
+            std::fs::copy(&asset.path, dest)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use avenue::ProcessExt;
+
+    use super::*;
+
+    #[test]
+    fn skips_hidden_files() {
+        let dir = std::env::temp_dir()
+            .join(format!("avenue-build-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let (src, out) = (dir.join("assets"), dir.join("out"));
+        std::fs::create_dir_all(src.join("css")).unwrap();
+        std::fs::write(src.join("css/app.css"), "body{}").unwrap();
+        std::fs::write(src.join("logo.svg"), "<svg/>").unwrap();
+        std::fs::write(src.join(".DS_Store"), "").unwrap();
+
+        Build::new(&src)
+            .out_dir(&out)
+            .process("*.css", |asset| {
+                asset.map(|contents| [&contents[..], b"\n"].concat())
+            })
+            .run()
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read(out.join("css/app.css")).unwrap(),
+            b"body{}\n"
+        );
+        assert_eq!(std::fs::read(out.join("logo.svg")).unwrap(), b"<svg/>");
+        assert!(!out.join(".DS_Store").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,330 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Ident, ItemStruct, LitByteStr, LitStr, Token,
+};
+
+use crate::stage;
+
+pub struct Args {
+    /// The directory to embed, as written in the attribute.
+    pub dir: LitStr,
+    /// Processing rules, in the order they were declared.
+    pub rules: Vec<Rule>,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let dir = input.parse()?;
+        let mut rules = Vec::new();
+
+        if !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            while !input.is_empty() {
+                rules.push(input.parse()?);
+            }
+        }
+
+        Ok(Self { dir, rules })
+    }
+}
+
+/// A glob and the stages to run on the files it matches, e.g.
+/// `"*.css" => minify_css, compress_brotli;`.
+pub struct Rule {
+    matcher: globset::GlobMatcher,
+    stages: Vec<Ident>,
+}
+
+impl Parse for Rule {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let glob: LitStr = input.parse()?;
+        let matcher = globset::Glob::new(&glob.value())
+            .map_err(|err| syn::Error::new(glob.span(), err))?
+            .compile_matcher();
+        input.parse::<Token![=>]>()?;
+
+        let mut stages = vec![input.parse()?];
+        while input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+            stages.push(input.parse()?);
+        }
+        if !input.is_empty() {
+            input.parse::<Token![;]>()?;
+        }
+
+        Ok(Self { matcher, stages })
+    }
+}
+
+/// A single file found in the embedded directory.
+struct Entry {
+    /// The forward-slash separated path relative to the embedded directory.
+    key: String,
+    /// The absolute path to the file.
+    path: PathBuf,
+}
+
+pub fn expand(args: Args, item: ItemStruct) -> syn::Result<TokenStream> {
+    if !item.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &item.generics,
+            "`#[embed]` does not support generic structs",
+        ));
+    }
+
+    let dir = resolve_dir(&args.dir)?;
+    let entries =
+        collect(&dir).map_err(|err| syn::Error::new(args.dir.span(), err))?;
+
+    let mut seen = BTreeMap::new();
+    let mut idents = Vec::with_capacity(entries.len());
+    let mut consts = Vec::with_capacity(entries.len());
+    let mut live_paths = Vec::with_capacity(entries.len());
+
+    for entry in &entries {
+        let ident = const_ident(&entry.key);
+        if let Some(other) = seen.insert(ident.to_string(), &entry.key) {
+            return Err(syn::Error::new(
+                args.dir.span(),
+                format!(
+                    "`{}` and `{}` both map to the constant `{}`",
+                    other, entry.key, ident
+                ),
+            ));
+        }
+
+        let key = &entry.key;
+        let path = entry.path.to_str().ok_or_else(|| {
+            syn::Error::new(
+                args.dir.span(),
+                format!("path to `{key}` is not valid UTF-8"),
+            )
+        })?;
+        let doc = format!("`{key}`");
+
+        let rule = args.rules.iter().find(|rule| rule.matcher.is_match(key));
+        let contents = match rule {
+            Some(rule) => {
+                let mut contents =
+                    std::fs::read(&entry.path).map_err(|err| {
+                        syn::Error::new(
+                            args.dir.span(),
+                            format!("failed to read `{key}`: {err}"),
+                        )
+                    })?;
+                for stage in &rule.stages {
+                    contents = stage::apply(stage, key, contents)?;
+                }
+                let contents = LitByteStr::new(&contents, Span::call_site());
+
+                // Only the processed bytes are embedded, but the source is
+                // still included so cargo tracks changes to it. The raw
+                // source can stand in for minified output in debug builds,
+                // but not for compressed output.
+                if rule.stages.iter().any(stage::is_compression) {
+                    live_paths.push(quote!(::core::option::Option::None));
+                } else {
+                    live_paths
+                        .push(quote!(::core::option::Option::Some(#path)));
+                }
+                quote! {{
+                    const _: &[u8] = include_bytes!(#path);
+                    #contents
+                }}
+            }
+            None => {
+                live_paths.push(quote!(::core::option::Option::Some(#path)));
+                quote!(include_bytes!(#path))
+            }
+        };
+
+        consts.push(quote! {
+            #[doc = #doc]
+            pub const #ident: ::avenue::BufAsset<'static, &'static str> =
+                ::avenue::BufAsset::from_static(#key, #contents);
+        });
+        idents.push(ident);
+    }
+
+    let name = &item.ident;
+    let asset_ty = quote!(::avenue::BufAsset<'static, &'static str>);
+
+    let mut index = phf_codegen::Map::new();
+    index.phf_path("::avenue::__private::phf");
+    for (i, entry) in entries.iter().enumerate() {
+        index.entry(entry.key.as_str(), &i.to_string());
+    }
+    let index: TokenStream = index
+        .build()
+        .to_string()
+        .parse()
+        .expect("phf_codegen should emit valid tokens");
+
+    Ok(quote! {
+        #item
+
+        impl #name {
+            #(#consts)*
+        }
+
+        ::avenue::__embed_lookup! {
+        impl #name {
+            #[doc(hidden)]
+            const __ENTRIES: &'static [#asset_ty] = &[#(Self::#idents),*];
+
+            #[doc(hidden)]
+            fn __index(key: &str) -> ::core::option::Option<usize> {
+                static INDEX: ::avenue::__private::phf::Map<&'static str, usize> =
+                    #index;
+                INDEX.get(key).copied()
+            }
+
+            /// Returns the embedded asset with the given key, if any.
+            pub fn get(key: &str) -> ::core::option::Option<&'static #asset_ty> {
+                Self::__index(key).map(|i| &Self::__ENTRIES[i])
+            }
+
+            /// Loads the asset with the given key, if any.
+            ///
+            /// In debug builds, assets that are not compressed are read from
+            /// their unprocessed source file on every access. Otherwise the
+            /// embedded bytes are returned.
+            pub fn load(
+                key: &str,
+            ) -> ::core::option::Option<::avenue::Asset<'static, 'static, &'static str>> {
+                let i = Self::__index(key)?;
+                let asset = &Self::__ENTRIES[i];
+
+                #[cfg(debug_assertions)]
+                {
+                    const PATHS: &[::core::option::Option<&str>] = &[#(#live_paths),*];
+                    if let ::core::option::Option::Some(path) = PATHS[i] {
+                        return ::core::option::Option::Some(::avenue::Asset::new_file(
+                            asset.key,
+                            ::avenue::__private::Path::new(path),
+                        ));
+                    }
+                }
+
+                ::core::option::Option::Some(::avenue::Asset::Buf(asset.clone()))
+            }
+
+            /// Returns an iterator over every embedded asset, ordered by key.
+            pub fn iter() -> ::core::slice::Iter<'static, #asset_ty> {
+                Self::__ENTRIES.iter()
+            }
+        }
+        }
+    })
+}
+
+/// Expands `$ENV_VAR` segments and resolves relative paths against
+/// `CARGO_MANIFEST_DIR`.
+fn resolve_dir(lit: &LitStr) -> syn::Result<PathBuf> {
+    let raw = lit.value();
+    let mut expanded = String::with_capacity(raw.len());
+    let mut rest = raw.as_str();
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        let name = &rest[..end];
+        if name.is_empty() {
+            return Err(syn::Error::new(
+                lit.span(),
+                "expected an environment variable name after `$`",
+            ));
+        }
+
+        let value = std::env::var(name).map_err(|_| {
+            syn::Error::new(
+                lit.span(),
+                format!("environment variable `{name}` is not set"),
+            )
+        })?;
+        expanded.push_str(&value);
+        rest = &rest[end..];
+    }
+    expanded.push_str(rest);
+
+    let path = PathBuf::from(expanded);
+    if path.is_absolute() {
+        return Ok(path);
+    }
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").map_err(|_| {
+        syn::Error::new(lit.span(), "`CARGO_MANIFEST_DIR` is not set")
+    })?;
+    Ok(Path::new(&manifest_dir).join(path))
+}
+
+/// Recursively collects every file in `dir`, sorted by key.
+fn collect(dir: &Path) -> Result<Vec<Entry>, String> {
+    let mut entries = Vec::new();
+
+    for res in walkdir::WalkDir::new(dir)
+        .follow_links(true)
+        .sort_by_file_name()
+    {
+        let dir_entry = res.map_err(|err| {
+            format!("failed to read `{}`: {}", dir.display(), err)
+        })?;
+        if !dir_entry.file_type().is_file() {
+            continue;
+        }
+
+        let rel = dir_entry
+            .path()
+            .strip_prefix(dir)
+            .expect("walked path should be inside the root");
+        let mut key = String::new();
+        for component in rel.components() {
+            let component =
+                component.as_os_str().to_str().ok_or_else(|| {
+                    format!("`{}` is not valid UTF-8", rel.display())
+                })?;
+            if !key.is_empty() {
+                key.push('/');
+            }
+            key.push_str(component);
+        }
+
+        let path = dir_entry.path().canonicalize().map_err(|err| {
+            format!("failed to resolve `{}`: {}", rel.display(), err)
+        })?;
+        entries.push(Entry { key, path });
+    }
+
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(entries)
+}
+
+/// Derives a constant name from a key, e.g. `css/style.css` becomes
+/// `CSS_STYLE_CSS`.
+fn const_ident(key: &str) -> Ident {
+    let mut name: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.starts_with(|c: char| c.is_ascii_digit()) || name == "_" {
+        name.insert(0, '_');
+    }
+    Ident::new(&name, Span::call_site())
+}
@@ -0,0 +1,91 @@
+mod embed;
+mod stage;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, ItemStruct};
+
+/// Embeds every file in a directory as a [`BufAsset`] constant.
+///
+/// The generated code requires the `embed` feature of `avenue`; without it,
+/// the macro fails to compile with an error saying so.
+///
+/// The directory may start with (or contain) `$ENV_VAR` segments, which are
+/// expanded at compile time. Relative paths are resolved against the
+/// directory of the crate's `Cargo.toml`.
+///
+/// Each file becomes an associated constant named after its relative path,
+/// e.g. `css/style.css` becomes `CSS_STYLE_CSS`. The contents are pulled in
+/// with [`include_bytes!`], so cargo recompiles when an embedded file
+/// changes. Files added to or removed from the directory are only picked up
+/// on the next recompilation of the crate.
+///
+/// ```ignore
+/// use avenue_macros::embed;
+///
+/// #[embed("$OUT_DIR/assets")]
+/// struct Assets;
+///
+/// let css = Assets::STYLE_CSS;
+/// ```
+///
+/// # Lookup
+///
+/// Besides the constants, the struct gets a `get` function that looks up an
+/// asset by key using a compile-time perfect hash map, and an `iter` function
+/// over every embedded asset in key order.
+///
+/// ```ignore
+/// let asset = Assets::get("style.css").unwrap();
+///
+/// for asset in Assets::iter() {
+///     println!("{}", asset.key);
+/// }
+/// ```
+///
+/// # Live loading
+///
+/// The generated `load` function returns an [`Asset`] instead of a
+/// reference. In debug builds it points at the source file, so edits are
+/// visible without recompiling, while release builds return the embedded
+/// bytes. This also applies to assets matched by a processing rule, so debug
+/// builds return the raw source instead of e.g. the minified output. Assets
+/// with a `compress_*` stage are always returned from the embedded bytes,
+/// since their sources are not in the expected content encoding.
+///
+/// ```ignore
+/// let asset = Assets::load("style.css").unwrap();
+/// let contents = asset.process_full()?;
+/// ```
+///
+/// # Processing
+///
+/// Files can be processed at compile time by listing glob rules after the
+/// directory. Each rule names one or more [`Builder`] adapters, which run in
+/// order on every file the glob matches. Only the first matching rule is
+/// applied, and files without a matching rule are embedded as-is.
+///
+/// ```ignore
+/// #[embed("$OUT_DIR/assets",
+///     "*.css" => minify_css, compress_brotli;
+///     "*.js" => minify_js;
+/// )]
+/// struct Assets;
+/// ```
+///
+/// The available stages are `compress_brotli`, `compress_deflate`,
+/// `compress_gzip`, `compress_zstd`, `minify_js`, `minify_css`,
+/// `minify_html` and `minify`, each gated behind the same feature as the
+/// corresponding adapter in `avenue-web`.
+///
+/// [`Asset`]: https://docs.rs/avenue/latest/avenue/enum.Asset.html
+/// [`BufAsset`]: https://docs.rs/avenue/latest/avenue/struct.BufAsset.html
+/// [`Builder`]: https://docs.rs/avenue-web/latest/avenue_web/trait.Builder.html
+#[proc_macro_attribute]
+pub fn embed(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(attr as embed::Args);
+    let item = parse_macro_input!(item as ItemStruct);
+
+    embed::expand(args, item)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,32 @@
+use avenue::Stage;
+use avenue_web::WebStage;
+use syn::Ident;
+
+/// Runs the [`WebStage`] named by `stage` over `contents`.
+pub fn apply(
+    stage: &Ident,
+    key: &str,
+    contents: Vec<u8>,
+) -> syn::Result<Vec<u8>> {
+    let name = stage.to_string();
+
+    let web_stage = name.parse::<WebStage>().map_err(|_| {
+        syn::Error::new(
+            stage.span(),
+            format!(
+                "unknown stage `{name}`, make sure the matching feature of \
+                 `avenue-macros` is enabled"
+            ),
+        )
+    })?;
+
+    web_stage
+        .apply(key, contents)
+        .map_err(|err| syn::Error::new(stage.span(), err.with_key(key)))
+}
+
+/// Whether `stage` changes the content encoding, in which case the source
+/// file cannot stand in for the processed output.
+pub fn is_compression(stage: &Ident) -> bool {
+    stage.to_string().starts_with("compress_")
+}
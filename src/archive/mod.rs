@@ -0,0 +1,9 @@
+#[cfg(feature = "tar")]
+mod tar;
+#[cfg(feature = "zip")]
+mod zip;
+
+#[cfg(feature = "tar")]
+pub use self::tar::TarSink;
+#[cfg(feature = "zip")]
+pub use self::zip::{ZipSink, ZipSource};
@@ -0,0 +1,163 @@
+use alloc::vec::Vec;
+use std::{
+    io::{self, Read, Write},
+    path::Path,
+};
+
+use crate::{
+    source::{is_safe_key, path_key},
+    AssetSink, BufAsset, Error, MemorySource,
+};
+
+impl MemorySource<'static> {
+    /// Reads every file of a `.tar` or `.tar.gz` archive into memory.
+    ///
+    /// Gzip compression is detected from the contents. Keys are the
+    /// forward-slash separated paths inside the archive, and entries other
+    /// than regular files are skipped. Archives may come from untrusted
+    /// sources, so entries whose paths have `..`, absolute or hidden
+    /// components are skipped too.
+    pub fn from_tar<R: Read>(reader: R) -> Result<Self, Error> {
+        use std::io::BufRead as _;
+
+        let mut reader = io::BufReader::new(reader);
+        let is_gzip = reader
+            .fill_buf()
+            .map_err(Error::io)?
+            .starts_with(&[0x1f, 0x8b]);
+        if is_gzip {
+            read_tar(flate2::read::GzDecoder::new(reader))
+        } else {
+            read_tar(reader)
+        }
+    }
+
+    /// Reads every file of a `.tar` or `.tar.gz` archive at `path` into
+    /// memory.
+    ///
+    /// See [`MemorySource::from_tar`].
+    #[inline]
+    pub fn from_tar_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_tar(std::fs::File::open(path).map_err(Error::io)?)
+    }
+}
+
+fn read_tar<R: Read>(reader: R) -> Result<MemorySource<'static>, Error> {
+    let mut archive = tar::Archive::new(reader);
+    let mut source = MemorySource::new();
+    for entry in archive.entries().map_err(Error::io)? {
+        let mut entry = entry.map_err(Error::io)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path().map_err(Error::io)?;
+        let key = path_key(path.strip_prefix(".").unwrap_or(&path))?;
+        if !is_safe_key(&key) {
+            continue;
+        }
+        let mut contents = Vec::new();
+        entry
+            .read_to_end(&mut contents)
+            .map_err(|err| Error::io(err).with_key(key.as_str()))?;
+        source.insert(BufAsset::new(key, contents));
+    }
+    Ok(source)
+}
+
+/// An [`AssetSink`] writing a tar archive.
+///
+/// For a `.tar.gz` archive, wrap the writer in a gzip encoder and finish it
+/// after [`TarSink::finish`].
+pub struct TarSink<W: Write> {
+    builder: tar::Builder<W>,
+}
+
+impl<W: Write> TarSink<W> {
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Self {
+            builder: tar::Builder::new(writer),
+        }
+    }
+
+    /// Writes the end of the archive, returning the writer.
+    pub fn finish(self) -> Result<W, Error> {
+        self.builder.into_inner().map_err(Error::io)
+    }
+}
+
+impl<W: Write> AssetSink for TarSink<W> {
+    fn write(&mut self, key: &str, contents: &[u8]) -> Result<(), Error> {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_entry_type(tar::EntryType::Regular);
+        self.builder
+            .append_data(&mut header, key, contents)
+            .map_err(|err| Error::io(err).with_key(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+
+    use super::*;
+    use crate::{AssetSource, Process};
+
+    fn contents(source: &MemorySource<'_>, key: &str) -> Vec<u8> {
+        let asset = source.open(key).unwrap().unwrap();
+        asset.process_full().unwrap().into_owned()
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut sink = TarSink::new(Vec::new());
+        sink.write("css/app.css", b"body{}").unwrap();
+        sink.write("logo.svg", b"<svg/>").unwrap();
+        let tar = sink.finish().unwrap();
+
+        let source = MemorySource::from_tar(&tar[..]).unwrap();
+        assert_eq!(source.keys().unwrap(), ["css/app.css", "logo.svg"]);
+        assert_eq!(contents(&source, "css/app.css"), b"body{}");
+
+        let mut gz = flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        );
+        gz.write_all(&tar).unwrap();
+        let source = MemorySource::from_tar(&gz.finish().unwrap()[..]).unwrap();
+        assert_eq!(contents(&source, "logo.svg"), b"<svg/>");
+    }
+
+    #[test]
+    fn skips_unsafe_paths() {
+        let mut builder = tar::Builder::new(Vec::new());
+        for name in [
+            "../../evil.txt",
+            "/abs.txt",
+            "a/../../evil.txt",
+            "a\\b.txt",
+            "C:/evil.txt",
+            ".env",
+            "./ok.txt",
+            "dir/ok.txt",
+        ] {
+            // `Header::set_path` rejects these, so write the name directly.
+            let mut header = tar::Header::new_old();
+            header.as_old_mut().name[..name.len()]
+                .copy_from_slice(name.as_bytes());
+            header.set_size(2);
+            header.set_mode(0o644);
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_cksum();
+            builder.append(&header, &b"ok"[..]).unwrap();
+        }
+        let tar = builder.into_inner().unwrap();
+
+        let source = MemorySource::from_tar(&tar[..]).unwrap();
+        let keys: Vec<String> = source.keys().unwrap();
+        assert_eq!(keys, ["dir/ok.txt", "ok.txt"]);
+    }
+}
@@ -0,0 +1,170 @@
+use alloc::{string::String, vec::Vec};
+use std::{
+    io::{Read, Seek, Write},
+    path::Path,
+    sync::Mutex,
+};
+
+use crate::{source::is_safe_key, Asset, AssetSink, AssetSource, Error};
+
+/// An [`AssetSource`] reading files from a zip archive.
+///
+/// Unlike tar archives, zip archives support random access, so files are
+/// only decompressed when they are opened. Archives may come from untrusted
+/// sources, so files whose names have `..`, absolute or hidden components are
+/// neither listed nor opened.
+pub struct ZipSource<R> {
+    archive: Mutex<zip::ZipArchive<R>>,
+}
+
+impl ZipSource<std::fs::File> {
+    /// Opens the zip archive at `path`.
+    #[inline]
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::new(std::fs::File::open(path).map_err(Error::io)?)
+    }
+}
+
+impl<R: Read + Seek> ZipSource<R> {
+    pub fn new(reader: R) -> Result<Self, Error> {
+        let archive = zip::ZipArchive::new(reader).map_err(zip_err)?;
+        Ok(Self {
+            archive: Mutex::new(archive),
+        })
+    }
+}
+
+/// The most bytes preallocated for an entry, based on its declared size.
+const MAX_PREALLOC: u64 = 1024 * 1024;
+
+impl<R: Read + Seek> AssetSource for ZipSource<R> {
+    fn keys(&self) -> Result<Vec<String>, Error> {
+        let archive =
+            self.archive.lock().unwrap_or_else(|err| err.into_inner());
+        let mut keys: Vec<String> = archive
+            .file_names()
+            .filter(|name| !name.ends_with('/') && is_safe_key(name))
+            .map(String::from)
+            .collect();
+        keys.sort_unstable();
+        Ok(keys)
+    }
+
+    fn open(&self, key: &str) -> Result<Option<Asset<'_, '_, String>>, Error> {
+        if !is_safe_key(key) {
+            return Ok(None);
+        }
+
+        let mut archive =
+            self.archive.lock().unwrap_or_else(|err| err.into_inner());
+        let mut file = match archive.by_name(key) {
+            Ok(file) if file.is_file() => file,
+            Ok(_) | Err(zip::result::ZipError::FileNotFound) => {
+                return Ok(None)
+            }
+            Err(err) => return Err(zip_err(err).with_key(key)),
+        };
+
+        // The size comes from an untrusted header, so only use it as a hint.
+        let capacity = file.size().min(MAX_PREALLOC) as usize;
+        let mut contents = Vec::with_capacity(capacity);
+        file.read_to_end(&mut contents)
+            .map_err(|err| Error::io(err).with_key(key))?;
+        Ok(Some(Asset::new_buf(String::from(key), contents)))
+    }
+}
+
+/// An [`AssetSink`] writing a zip archive.
+///
+/// Files are compressed with deflate.
+pub struct ZipSink<W: Write + Seek> {
+    writer: zip::ZipWriter<W>,
+}
+
+impl<W: Write + Seek> ZipSink<W> {
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: zip::ZipWriter::new(writer),
+        }
+    }
+
+    /// Writes the central directory, returning the writer.
+    pub fn finish(self) -> Result<W, Error> {
+        self.writer.finish().map_err(zip_err)
+    }
+}
+
+impl<W: Write + Seek> AssetSink for ZipSink<W> {
+    fn write(&mut self, key: &str, contents: &[u8]) -> Result<(), Error> {
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        self.writer
+            .start_file(key, options)
+            .map_err(zip_err)
+            .and_then(|()| self.writer.write_all(contents).map_err(Error::io))
+            .map_err(|err| err.with_key(key))
+    }
+}
+
+fn zip_err(err: zip::result::ZipError) -> Error {
+    match err {
+        zip::result::ZipError::Io(err) => Error::io(err),
+        err => Error::parse(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::Process;
+
+    fn zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            let options = zip::write::SimpleFileOptions::default();
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(contents).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut sink = ZipSink::new(Cursor::new(Vec::new()));
+        sink.write("css/app.css", b"body{}").unwrap();
+        sink.write("logo.svg", b"<svg/>").unwrap();
+        let zip = sink.finish().unwrap().into_inner();
+
+        let source = ZipSource::new(Cursor::new(zip)).unwrap();
+        assert_eq!(source.keys().unwrap(), ["css/app.css", "logo.svg"]);
+        let asset = source.open("css/app.css").unwrap().unwrap();
+        assert_eq!(asset.process_full().unwrap(), &b"body{}"[..]);
+        assert!(source.open("missing.css").unwrap().is_none());
+    }
+
+    #[test]
+    fn skips_unsafe_names() {
+        let unsafe_names = [
+            "../../evil.txt",
+            "/abs.txt",
+            "a/../../evil.txt",
+            "a\\b.txt",
+            "C:/evil.txt",
+            ".env",
+        ];
+        let mut files: Vec<(&str, &[u8])> = unsafe_names
+            .iter()
+            .map(|name| (*name, &b"no"[..]))
+            .collect();
+        files.push(("dir/ok.txt", b"ok"));
+
+        let source = ZipSource::new(Cursor::new(zip(&files))).unwrap();
+        assert_eq!(source.keys().unwrap(), ["dir/ok.txt"]);
+        for name in unsafe_names {
+            assert!(source.open(name).unwrap().is_none(), "{name}");
+        }
+    }
+}
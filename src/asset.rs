@@ -1,9 +1,13 @@
-use alloc::{borrow::Cow, vec::Vec};
-use core::{convert::Infallible, mem};
+use alloc::{borrow::Cow, string::String, vec::Vec};
+use core::{convert::Infallible, future::Future, mem};
 
-use crate::{AssetExt, BoxError, Process};
+use crate::{
+    AssetExt, AssetReader, ContentEncoding, Digest, Error, IntoProcessRead,
+    Process, ProcessAsync, ReadBuf,
+};
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BufAsset<'c, K> {
     /// A key identifying this asset.
     ///
@@ -11,6 +15,7 @@ pub struct BufAsset<'c, K> {
     /// could be anything.
     pub key: K,
     /// The asset contents.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_impls::bytes"))]
     pub contents: Cow<'c, [u8]>,
 }
 
@@ -34,6 +39,19 @@ impl<'c, K> BufAsset<'c, K> {
     }
 }
 
+impl<K> BufAsset<'static, K> {
+    /// Creates an asset borrowing static contents.
+    ///
+    /// Unlike [`BufAsset::new`], this can be used in constant expressions.
+    #[inline]
+    pub const fn from_static(key: K, contents: &'static [u8]) -> Self {
+        Self {
+            key,
+            contents: Cow::Borrowed(contents),
+        }
+    }
+}
+
 impl<K> AsRef<[u8]> for BufAsset<'_, K> {
     fn as_ref(&self) -> &[u8] {
         &self.contents
@@ -50,6 +68,28 @@ impl<'c, K> Process for BufAsset<'c, K> {
     }
 }
 
+impl<'c, K> ProcessAsync for BufAsset<'c, K> {
+    type Error = Infallible;
+    type Output = Cow<'c, [u8]>;
+
+    #[inline]
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        core::future::ready(Ok(self.contents))
+    }
+}
+
+impl<'c, K> IntoProcessRead for BufAsset<'c, K> {
+    type Error = Infallible;
+    type Reader = ReadBuf<'c>;
+
+    #[inline]
+    fn into_process_read(self) -> Result<Self::Reader, Self::Error> {
+        Ok(ReadBuf::new(self.contents))
+    }
+}
+
 #[cfg(feature = "std")]
 impl<K> AssetExt for BufAsset<'_, K>
 where
@@ -58,7 +98,9 @@ where
     #[cfg(feature = "mime")]
     #[inline]
     fn mime(&self) -> Option<mime::Mime> {
-        mime_guess::MimeGuess::from_path(&self.key).first()
+        let key = self.key.as_ref();
+        crate::registry::guess_mime(Some(key), key)
+            .or_else(|| crate::sniff_mime(&self.contents))
     }
 
     #[inline]
@@ -70,10 +112,16 @@ where
     fn size_hint(&self) -> Option<usize> {
         Some(self.contents.len())
     }
+
+    #[inline]
+    fn etag(&self) -> Option<String> {
+        Some(Digest::sha256(&self.contents).etag())
+    }
 }
 
 #[cfg(feature = "std")]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FileAsset<'p, K> {
     /// A key identifying this asset.
     ///
@@ -105,6 +153,20 @@ impl<'p, K> FileAsset<'p, K> {
     }
 }
 
+#[cfg(feature = "mime")]
+impl<K: AsRef<std::path::Path>> FileAsset<'_, K> {
+    /// Like [`AssetExt::mime`], but checks the
+    /// [`MimeRegistry`](crate::MimeRegistry) override for the key first.
+    ///
+    /// [`AssetExt::mime`] only looks at the path, since the key of a file
+    /// asset is not necessarily path-like.
+    #[inline]
+    pub fn mime_with_key(&self) -> Option<mime::Mime> {
+        crate::registry::guess_mime(Some(self.key.as_ref()), &self.path)
+            .or_else(|| crate::sniff::sniff_file(&self.path))
+    }
+}
+
 #[cfg(feature = "std")]
 impl<K> Process for FileAsset<'_, K> {
     type Error = std::io::Error;
@@ -116,12 +178,37 @@ impl<K> Process for FileAsset<'_, K> {
     }
 }
 
+#[cfg(feature = "tokio")]
+impl<K> ProcessAsync for FileAsset<'_, K> {
+    type Error = std::io::Error;
+    type Output = Vec<u8>;
+
+    #[inline]
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        tokio::fs::read(self.path)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K> IntoProcessRead for FileAsset<'_, K> {
+    type Error = std::io::Error;
+    type Reader = std::fs::File;
+
+    #[inline]
+    fn into_process_read(self) -> Result<Self::Reader, Self::Error> {
+        std::fs::File::open(self.path)
+    }
+}
+
 #[cfg(feature = "std")]
 impl<K> AssetExt for FileAsset<'_, K> {
     #[cfg(feature = "mime")]
     #[inline]
     fn mime(&self) -> Option<mime::Mime> {
-        mime_guess::MimeGuess::from_path(&self.path).first()
+        crate::registry::guess_mime(None, &self.path)
+            .or_else(|| crate::sniff::sniff_file(&self.path))
     }
 
     #[inline]
@@ -133,9 +220,28 @@ impl<K> AssetExt for FileAsset<'_, K> {
     fn size_hint(&self) -> Option<usize> {
         std::fs::metadata(&self.path).map(|m| m.len() as usize).ok()
     }
+
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        std::fs::metadata(&self.path)
+            .and_then(|m| m.modified())
+            .ok()
+    }
+
+    #[inline]
+    fn etag(&self) -> Option<String> {
+        Digest::sha256_file(&self.path)
+            .ok()
+            .map(|digest| digest.etag())
+    }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
 pub enum Asset<'p, 'c, K> {
     Buf(BufAsset<'c, K>),
     #[cfg(feature = "std")]
@@ -179,6 +285,7 @@ impl<'p, 'c, K> Asset<'p, 'c, K> {
 }
 
 impl<'p, 'c, K: Default> Asset<'p, 'c, K> {
+    #[cfg(feature = "std")]
     pub fn into_buf(self) -> std::io::Result<BufAsset<'c, K>> {
         match self {
             Asset::Buf(buf) => Ok(buf),
@@ -186,6 +293,7 @@ impl<'p, 'c, K: Default> Asset<'p, 'c, K> {
         }
     }
 
+    #[cfg(feature = "std")]
     pub fn ensure_buf(&mut self) -> std::io::Result<()> {
         if let Asset::File(asset) = self {
             let tmp = FileAsset {
@@ -227,7 +335,7 @@ impl<'p, 'c, K: Default> Asset<'p, 'c, K> {
 }
 
 impl<'c, K> Process for Asset<'_, 'c, K> {
-    type Error = BoxError;
+    type Error = Error;
     type Output = Cow<'c, [u8]>;
 
     fn process_full(self) -> Result<Self::Output, Self::Error> {
@@ -236,10 +344,55 @@ impl<'c, K> Process for Asset<'_, 'c, K> {
                 buf_asset.process_full().map_err(Into::into)
             }
             #[cfg(feature = "std")]
-            Asset::File(file_asset) => file_asset
-                .process_full()
+            Asset::File(file_asset) => {
+                file_asset.process_full().map_err(Error::io).map(Into::into)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<'c, K> ProcessAsync for Asset<'_, 'c, K> {
+    type Error = Error;
+    type Output = Cow<'c, [u8]>;
+
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        // Only the contents or path are moved into the future, so it is
+        // `Send` regardless of the key type.
+        let src = match self {
+            Asset::Buf(buf_asset) => Ok(buf_asset.contents),
+            Asset::File(file_asset) => Err(file_asset.path),
+        };
+
+        async move {
+            match src {
+                Ok(contents) => Ok(contents),
+                Err(path) => tokio::fs::read(path)
+                    .await
+                    .map(Into::into)
+                    .map_err(Error::io),
+            }
+        }
+    }
+}
+
+impl<'c, K> IntoProcessRead for Asset<'_, 'c, K> {
+    type Error = Error;
+    type Reader = AssetReader<'c>;
+
+    fn into_process_read(self) -> Result<Self::Reader, Self::Error> {
+        match self {
+            Asset::Buf(buf_asset) => buf_asset
+                .into_process_read()
                 .map_err(Into::into)
-                .map(Into::into),
+                .map(AssetReader::Buf),
+            #[cfg(feature = "std")]
+            Asset::File(file_asset) => file_asset
+                .into_process_read()
+                .map_err(Error::io)
+                .map(AssetReader::File),
         }
     }
 }
@@ -254,7 +407,7 @@ where
     fn mime(&self) -> Option<mime::Mime> {
         match self {
             Asset::Buf(buf_asset) => buf_asset.mime(),
-            Asset::File(file_asset) => file_asset.mime(),
+            Asset::File(file_asset) => file_asset.mime_with_key(),
         }
     }
 
@@ -273,6 +426,30 @@ where
             Asset::File(file_asset) => file_asset.size_hint(),
         }
     }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        match self {
+            Asset::Buf(buf_asset) => buf_asset.content_encoding(),
+            Asset::File(file_asset) => file_asset.content_encoding(),
+        }
+    }
+
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        match self {
+            Asset::Buf(buf_asset) => buf_asset.last_modified(),
+            Asset::File(file_asset) => file_asset.last_modified(),
+        }
+    }
+
+    #[inline]
+    fn etag(&self) -> Option<String> {
+        match self {
+            Asset::Buf(buf_asset) => buf_asset.etag(),
+            Asset::File(file_asset) => file_asset.etag(),
+        }
+    }
 }
 
 impl<'c, K> From<BufAsset<'c, K>> for Asset<'_, 'c, K> {
@@ -287,3 +464,30 @@ impl<'p, K> From<FileAsset<'p, K>> for Asset<'p, '_, K> {
         Self::File(value)
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_etag_matches_contents() {
+        let path = std::env::temp_dir()
+            .join(alloc::format!("avenue-etag-{}", std::process::id()));
+        std::fs::write(&path, b"body{}").unwrap();
+
+        let file = FileAsset::new("app.css", path.as_path());
+        let buf = BufAsset::new("app.css", &b"body{}"[..]);
+        assert_eq!(file.etag(), buf.etag());
+        assert_eq!(Asset::from(file.clone()).etag(), buf.etag());
+
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(file.etag(), None);
+    }
+
+    #[test]
+    fn file_asset_ext_for_any_key() {
+        fn assert_asset_ext<T: AssetExt>() {}
+        assert_asset_ext::<FileAsset<'static, ()>>();
+        assert_asset_ext::<FileAsset<'static, u32>>();
+    }
+}
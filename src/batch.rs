@@ -0,0 +1,50 @@
+use alloc::vec::Vec;
+
+use crate::Process;
+
+/// Processes every item, returning the results in the same order.
+///
+/// With the `rayon` feature the items are processed in parallel on the
+/// global rayon thread pool, otherwise they are processed one after another.
+/// Either way a failing item does not stop the others from being processed.
+///
+/// ```ignore
+/// let results = avenue::process_batch(
+///     assets.into_iter().map(|asset| asset.minify_css().compress_brotli()),
+/// );
+/// ```
+pub fn process_batch<I, T>(items: I) -> Vec<Result<T::Output, T::Error>>
+where
+    I: IntoIterator<Item = T>,
+    T: Process + Send,
+    T::Output: Send,
+    T::Error: Send,
+{
+    map_batch(items, Process::process_full)
+}
+
+/// Maps every item with `f`, returning the results in the same order.
+///
+/// This is the building block of [`process_batch`], for work that does not
+/// fit a single [`Process`], such as building a process chain per item and
+/// writing its output. It runs in parallel under the same conditions.
+pub fn map_batch<I, F, R>(items: I, f: F) -> Vec<R>
+where
+    I: IntoIterator,
+    I::Item: Send,
+    F: Fn(I::Item) -> R + Send + Sync,
+    R: Send,
+{
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        let items: Vec<_> = items.into_iter().collect();
+        items.into_par_iter().map(f).collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        items.into_iter().map(f).collect()
+    }
+}
@@ -0,0 +1,264 @@
+use alloc::{borrow::Cow, format, string::String, vec::Vec};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{Digest, Error, Process, Stage};
+
+/// A persistent cache of processed outputs.
+///
+/// Entries are keyed by a digest of the input bytes and a fingerprint of the
+/// processing configuration, so changing either results in a miss. Entries
+/// of [cached stages](Cache::stage) also include the asset key, since stages
+/// may behave differently per key, e.g. by picking a minifier from its mime
+/// type. Entries are written atomically, so concurrent builds sharing a cache
+/// directory never observe partial outputs.
+///
+/// ```ignore
+/// let cache = Cache::new("target/avenue-cache");
+///
+/// // Wrap a process chain, describing its configuration in the fingerprint.
+/// let css = cache.cached("minify_css,compress_brotli", asset, |contents| {
+///     BufAsset::new("style.css", contents)
+///         .minify_css()
+///         .compress_brotli()
+/// });
+///
+/// // Or wrap a stage, using its own fingerprint.
+/// let pipeline = cache.stage(pipeline);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    /// Creates a cache storing its entries in `dir`.
+    ///
+    /// The directory is created when the first entry is written.
+    #[inline]
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    #[inline]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the cached output for `input` processed with the
+    /// configuration described by `fingerprint`, if any.
+    #[inline]
+    pub fn get(
+        &self,
+        fingerprint: &str,
+        input: &[u8],
+    ) -> Result<Option<Vec<u8>>, Error> {
+        read_entry(&self.entry_path(fingerprint, "", input))
+    }
+
+    /// Stores the output for `input` processed with the configuration
+    /// described by `fingerprint`.
+    #[inline]
+    pub fn insert(
+        &self,
+        fingerprint: &str,
+        input: &[u8],
+        output: &[u8],
+    ) -> Result<(), Error> {
+        write_entry(&self.entry_path(fingerprint, "", input), output)
+    }
+
+    /// Wraps a process chain so its output is cached.
+    ///
+    /// `src` produces the input bytes, which are hashed together with
+    /// `fingerprint`. On a miss, `f` builds the process chain from the input
+    /// and its output is stored.
+    #[inline]
+    pub fn cached<'a, T, F, P>(
+        &'a self,
+        fingerprint: impl Into<Cow<'a, str>>,
+        src: T,
+        f: F,
+    ) -> Cached<'a, T, F>
+    where
+        T: Process,
+        F: FnOnce(T::Output) -> P,
+        P: Process,
+    {
+        Cached {
+            cache: self,
+            fingerprint: fingerprint.into(),
+            src,
+            f,
+        }
+    }
+
+    /// Wraps a stage so its outputs are cached, using its
+    /// [`fingerprint`](Stage::fingerprint) and the key of each asset.
+    #[inline]
+    pub fn stage<S: Stage>(&self, stage: S) -> CachedStage<S> {
+        CachedStage {
+            cache: self.clone(),
+            fingerprint: stage.fingerprint(),
+            stage,
+        }
+    }
+
+    fn entry_path(
+        &self,
+        fingerprint: &str,
+        key: &str,
+        input: &[u8],
+    ) -> PathBuf {
+        let fingerprint_len = (fingerprint.len() as u64).to_le_bytes();
+        let key_len = (key.len() as u64).to_le_bytes();
+        let digest = Digest::sha256_parts(&[
+            &fingerprint_len,
+            fingerprint.as_bytes(),
+            &key_len,
+            key.as_bytes(),
+            input,
+        ]);
+        let hex = digest.to_hex();
+        self.dir.join(&hex[..2]).join(&hex[2..])
+    }
+}
+
+fn read_entry(path: &Path) -> Result<Option<Vec<u8>>, Error> {
+    match fs::read(path) {
+        Ok(output) => Ok(Some(output)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(Error::io(err).with_stage("cache")),
+    }
+}
+
+fn write_entry(path: &Path, output: &[u8]) -> Result<(), Error> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let write = || {
+        let parent = path.parent().expect("entry should be in a directory");
+        fs::create_dir_all(parent)?;
+
+        // Write to a unique temporary file first, so readers only ever see
+        // complete entries.
+        let tmp = parent.join(format!(
+            ".tmp-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp, output)?;
+        fs::rename(&tmp, path).inspect_err(|_| {
+            let _ = fs::remove_file(&tmp);
+        })
+    };
+    write().map_err(|err| Error::io(err).with_stage("cache"))
+}
+
+/// A process chain whose output is cached.
+///
+/// Created by [`Cache::cached`].
+pub struct Cached<'a, T, F> {
+    cache: &'a Cache,
+    fingerprint: Cow<'a, str>,
+    src: T,
+    f: F,
+}
+
+impl<T, F, P> Process for Cached<'_, T, F>
+where
+    T: Process,
+    F: FnOnce(T::Output) -> P,
+    P: Process,
+{
+    type Error = Error;
+    type Output = Vec<u8>;
+
+    fn process_full(self) -> Result<Self::Output, Self::Error> {
+        let input = self.src.process_full().map_err(Error::other)?;
+        let path = self.cache.entry_path(&self.fingerprint, "", input.as_ref());
+        if let Some(output) = read_entry(&path)? {
+            return Ok(output);
+        }
+
+        let output = (self.f)(input).process_full().map_err(Error::other)?;
+        let output = output.as_ref().to_vec();
+        write_entry(&path, &output)?;
+        Ok(output)
+    }
+}
+
+/// A [`Stage`] whose outputs are cached.
+///
+/// Created by [`Cache::stage`].
+#[derive(Debug, Clone)]
+pub struct CachedStage<S> {
+    cache: Cache,
+    fingerprint: String,
+    stage: S,
+}
+
+impl<S: Stage> Stage for CachedStage<S> {
+    #[inline]
+    fn name(&self) -> &str {
+        self.stage.name()
+    }
+
+    #[inline]
+    fn fingerprint(&self) -> String {
+        self.fingerprint.clone()
+    }
+
+    fn apply(&self, key: &str, contents: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let path = self.cache.entry_path(&self.fingerprint, key, &contents);
+        if let Some(output) = read_entry(&path)? {
+            return Ok(output);
+        }
+
+        let output = self.stage.apply(key, contents)?;
+        write_entry(&path, &output)?;
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::from_fn;
+
+    fn temp_cache(name: &str) -> Cache {
+        let dir = std::env::temp_dir()
+            .join(format!("avenue-cache-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        Cache::new(dir)
+    }
+
+    #[test]
+    fn stage_entries_depend_on_key() {
+        let cache = temp_cache("key");
+        let stage = cache.stage(from_fn("key", |key, _| Ok(key.into())));
+
+        assert_eq!(stage.apply("a.css", b"x".to_vec()).unwrap(), b"a.css");
+        assert_eq!(stage.apply("a.js", b"x".to_vec()).unwrap(), b"a.js");
+        assert_eq!(stage.apply("a.css", b"x".to_vec()).unwrap(), b"a.css");
+
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+
+    #[test]
+    fn insert_then_get() {
+        let cache = temp_cache("get");
+
+        assert_eq!(cache.get("fp", b"in").unwrap(), None);
+        cache.insert("fp", b"in", b"out").unwrap();
+        assert_eq!(
+            cache.get("fp", b"in").unwrap().as_deref(),
+            Some(&b"out"[..])
+        );
+        assert_eq!(cache.get("other", b"in").unwrap(), None);
+
+        fs::remove_dir_all(cache.dir()).unwrap();
+    }
+}
@@ -0,0 +1,247 @@
+use core::future::Future;
+
+use crate::{
+    AssetExt, BoxError, ContentEncoding, Error, Process, ProcessAsync,
+};
+
+/// Transforms the output of a process with a closure.
+///
+/// Created by [`ProcessExt::map`](crate::ProcessExt::map).
+pub struct Map<T, F>(pub T, pub F);
+
+impl<T, F, O> Process for Map<T, F>
+where
+    T: Process,
+    F: FnOnce(T::Output) -> O,
+    O: AsRef<[u8]>,
+{
+    type Error = T::Error;
+    type Output = O;
+
+    #[inline]
+    fn process_full(self) -> Result<Self::Output, Self::Error> {
+        self.0.process_full().map(self.1)
+    }
+}
+
+impl<T, F, O> ProcessAsync for Map<T, F>
+where
+    T: ProcessAsync,
+    F: FnOnce(T::Output) -> O + Send,
+    O: AsRef<[u8]>,
+{
+    type Error = T::Error;
+    type Output = O;
+
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        let fut = self.0.process_async();
+        let f = self.1;
+        async move { fut.await.map(f) }
+    }
+}
+
+impl<T: AssetExt, F> AssetExt for Map<T, F> {
+    #[cfg(feature = "mime")]
+    #[inline]
+    fn mime(&self) -> Option<mime::Mime> {
+        self.0.mime()
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn path(&self) -> Option<&std::path::Path> {
+        self.0.path()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        // The closure may change the size of the output.
+        None
+    }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        self.0.content_encoding()
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        self.0.last_modified()
+    }
+}
+
+/// Transforms the output of a process with a fallible closure.
+///
+/// Created by [`ProcessExt::and_then`](crate::ProcessExt::and_then).
+pub struct AndThen<T, F>(pub T, pub F);
+
+impl<T, F, O, E> Process for AndThen<T, F>
+where
+    T: Process,
+    F: FnOnce(T::Output) -> Result<O, E>,
+    O: AsRef<[u8]>,
+    E: Into<BoxError>,
+{
+    type Error = Error;
+    type Output = O;
+
+    #[inline]
+    fn process_full(self) -> Result<Self::Output, Self::Error> {
+        let out = self.0.process_full().map_err(Error::other)?;
+        (self.1)(out).map_err(Error::other)
+    }
+}
+
+impl<T, F, O, E> ProcessAsync for AndThen<T, F>
+where
+    T: ProcessAsync,
+    F: FnOnce(T::Output) -> Result<O, E> + Send,
+    O: AsRef<[u8]>,
+    E: Into<BoxError>,
+{
+    type Error = Error;
+    type Output = O;
+
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        let fut = self.0.process_async();
+        let f = self.1;
+        async move {
+            let out = fut.await.map_err(Error::other)?;
+            f(out).map_err(Error::other)
+        }
+    }
+}
+
+impl<T: AssetExt, F> AssetExt for AndThen<T, F> {
+    #[cfg(feature = "mime")]
+    #[inline]
+    fn mime(&self) -> Option<mime::Mime> {
+        self.0.mime()
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn path(&self) -> Option<&std::path::Path> {
+        self.0.path()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        // The closure may change the size of the output.
+        None
+    }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        self.0.content_encoding()
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        self.0.last_modified()
+    }
+}
+
+/// Passes the result of a process, successful or not, to a closure.
+///
+/// Created by [`ProcessExt::then`](crate::ProcessExt::then).
+pub struct Then<T, F>(pub T, pub F);
+
+impl<T, F, O, E> Process for Then<T, F>
+where
+    T: Process,
+    F: FnOnce(Result<T::Output, T::Error>) -> Result<O, E>,
+    O: AsRef<[u8]>,
+    E: Into<BoxError>,
+{
+    type Error = Error;
+    type Output = O;
+
+    #[inline]
+    fn process_full(self) -> Result<Self::Output, Self::Error> {
+        (self.1)(self.0.process_full()).map_err(Error::other)
+    }
+}
+
+impl<T, F, O, E> ProcessAsync for Then<T, F>
+where
+    T: ProcessAsync,
+    F: FnOnce(Result<T::Output, T::Error>) -> Result<O, E> + Send,
+    O: AsRef<[u8]>,
+    E: Into<BoxError>,
+{
+    type Error = Error;
+    type Output = O;
+
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        let fut = self.0.process_async();
+        let f = self.1;
+        async move { f(fut.await).map_err(Error::other) }
+    }
+}
+
+impl<T: AssetExt, F> AssetExt for Then<T, F> {
+    #[cfg(feature = "mime")]
+    #[inline]
+    fn mime(&self) -> Option<mime::Mime> {
+        self.0.mime()
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn path(&self) -> Option<&std::path::Path> {
+        self.0.path()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        // The closure may change the size of the output.
+        None
+    }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        self.0.content_encoding()
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        self.0.last_modified()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{borrow::Cow, vec::Vec};
+
+    use crate::{BufAsset, ProcessExt};
+
+    use super::*;
+
+    fn repeat(out: Cow<'_, [u8]>) -> Vec<u8> {
+        out.repeat(2)
+    }
+
+    #[test]
+    fn size_hint_is_unknown() {
+        let asset = || BufAsset::new("a.txt", &b"abc"[..]);
+        assert_eq!(asset().size_hint(), Some(3));
+
+        assert_eq!(asset().map(repeat).size_hint(), None);
+        let f = |out| Ok::<_, Error>(repeat(out));
+        assert_eq!(asset().and_then(f).size_hint(), None);
+        let f = |out: Result<_, _>| out.map(repeat);
+        assert_eq!(asset().then(f).size_hint(), None);
+
+        assert_eq!(asset().map(repeat).process_full().unwrap(), b"abcabc");
+    }
+}
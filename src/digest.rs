@@ -0,0 +1,354 @@
+use alloc::{format, string::String};
+use core::{fmt, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use sha2::{Digest as _, Sha256, Sha384, Sha512};
+
+use crate::Error;
+
+/// A hash algorithm used to compute a [`Digest`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Algorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+    #[cfg(feature = "blake3")]
+    Blake3,
+    /// The 64-bit variant of XXH3.
+    ///
+    /// This is not a cryptographic hash, and should only be used for cache
+    /// keys and similar.
+    #[cfg(feature = "xxhash")]
+    Xxh3,
+}
+
+impl Algorithm {
+    /// Returns the name of this algorithm, as used in Subresource Integrity
+    /// strings for the SHA-2 family.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Algorithm::Sha256 => "sha256",
+            Algorithm::Sha384 => "sha384",
+            Algorithm::Sha512 => "sha512",
+            #[cfg(feature = "blake3")]
+            Algorithm::Blake3 => "blake3",
+            #[cfg(feature = "xxhash")]
+            Algorithm::Xxh3 => "xxh3",
+        }
+    }
+
+    /// The length of the digests produced by this algorithm, in bytes.
+    pub fn output_len(self) -> usize {
+        match self {
+            Algorithm::Sha256 => 32,
+            Algorithm::Sha384 => 48,
+            Algorithm::Sha512 => 64,
+            #[cfg(feature = "blake3")]
+            Algorithm::Blake3 => 32,
+            #[cfg(feature = "xxhash")]
+            Algorithm::Xxh3 => 8,
+        }
+    }
+
+    /// Whether browsers accept this algorithm in Subresource Integrity
+    /// strings.
+    pub fn is_sri(self) -> bool {
+        matches!(
+            self,
+            Algorithm::Sha256 | Algorithm::Sha384 | Algorithm::Sha512
+        )
+    }
+
+    /// Computes the digest of `data`.
+    pub fn digest(self, data: &[u8]) -> Digest {
+        let mut bytes = [0; MAX_LEN];
+        let len = self.output_len();
+        match self {
+            Algorithm::Sha256 => {
+                bytes[..len].copy_from_slice(&Sha256::digest(data))
+            }
+            Algorithm::Sha384 => {
+                bytes[..len].copy_from_slice(&Sha384::digest(data))
+            }
+            Algorithm::Sha512 => {
+                bytes[..len].copy_from_slice(&Sha512::digest(data))
+            }
+            #[cfg(feature = "blake3")]
+            Algorithm::Blake3 => {
+                bytes[..len].copy_from_slice(blake3::hash(data).as_bytes())
+            }
+            #[cfg(feature = "xxhash")]
+            Algorithm::Xxh3 => bytes[..len].copy_from_slice(
+                &xxhash_rust::xxh3::xxh3_64(data).to_be_bytes(),
+            ),
+        }
+        Digest {
+            algorithm: self,
+            bytes,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sha256" => Ok(Algorithm::Sha256),
+            "sha384" => Ok(Algorithm::Sha384),
+            "sha512" => Ok(Algorithm::Sha512),
+            #[cfg(feature = "blake3")]
+            "blake3" => Ok(Algorithm::Blake3),
+            #[cfg(feature = "xxhash")]
+            "xxh3" => Ok(Algorithm::Xxh3),
+            _ => Err(Error::parse(format!("unknown algorithm `{s}`"))),
+        }
+    }
+}
+
+const MAX_LEN: usize = 64;
+
+/// A digest of the contents of an asset.
+///
+/// ```
+/// use avenue::Algorithm;
+///
+/// let digest = Algorithm::Sha384.digest(b"alert('Hello, world.');");
+/// assert_eq!(
+///     digest.sri().unwrap(),
+///     "sha384-H8BRh8j48O9oYatfu5AZzq6A9RINhZO5H16dQZngK7T62em8MUt1FLm52t+eX6xO",
+/// );
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Digest {
+    algorithm: Algorithm,
+    bytes: [u8; MAX_LEN],
+}
+
+impl Digest {
+    /// Computes the SHA-256 digest of `data`.
+    #[inline]
+    pub fn sha256(data: &[u8]) -> Self {
+        Algorithm::Sha256.digest(data)
+    }
+
+    /// Creates a digest from its raw bytes, returning `None` if their length
+    /// does not match `algorithm`.
+    pub fn from_bytes(algorithm: Algorithm, bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != algorithm.output_len() {
+            return None;
+        }
+        let mut buf = [0; MAX_LEN];
+        buf[..bytes.len()].copy_from_slice(bytes);
+        Some(Self {
+            algorithm,
+            bytes: buf,
+        })
+    }
+
+    /// Computes the SHA-256 digest of the concatenation of `parts`.
+    pub(crate) fn sha256_parts(parts: &[&[u8]]) -> Self {
+        let mut hasher = Sha256::new();
+        for part in parts {
+            hasher.update(part);
+        }
+        let mut bytes = [0; MAX_LEN];
+        bytes[..32].copy_from_slice(&hasher.finalize());
+        Self {
+            algorithm: Algorithm::Sha256,
+            bytes,
+        }
+    }
+
+    /// Computes the SHA-256 digest of the file at `path`, without reading it
+    /// into memory at once.
+    #[cfg(feature = "std")]
+    pub(crate) fn sha256_file(path: &std::path::Path) -> std::io::Result<Self> {
+        use std::io::Read as _;
+
+        let mut file = std::fs::File::open(path)?;
+        let mut hasher = Sha256::new();
+        let mut buf = [0; 8 * 1024];
+        loop {
+            match file.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => hasher.update(&buf[..n]),
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        let mut bytes = [0; MAX_LEN];
+        bytes[..32].copy_from_slice(&hasher.finalize());
+        Ok(Self {
+            algorithm: Algorithm::Sha256,
+            bytes,
+        })
+    }
+
+    #[inline]
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.algorithm.output_len()]
+    }
+
+    /// Returns a strong HTTP `ETag` for this digest, including the
+    /// surrounding quotes.
+    pub fn etag(&self) -> String {
+        let mut etag = self.to_hex();
+        etag.truncate(32);
+        etag.insert(0, '"');
+        etag.push('"');
+        etag
+    }
+
+    /// Returns the lowercase hex encoding of this digest.
+    pub fn to_hex(&self) -> String {
+        use core::fmt::Write as _;
+
+        let mut hex = String::with_capacity(self.as_bytes().len() * 2);
+        for byte in self.as_bytes() {
+            let _ = write!(hex, "{byte:02x}");
+        }
+        hex
+    }
+
+    /// Returns the standard base64 encoding of this digest.
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.as_bytes())
+    }
+
+    /// Returns the Subresource Integrity string for this digest, e.g.
+    /// `sha384-...`, suitable for an `integrity` attribute.
+    ///
+    /// Returns `None` if the algorithm is not supported by SRI.
+    pub fn sri(&self) -> Option<String> {
+        if !self.algorithm.is_sri() {
+            return None;
+        }
+        let mut sri = String::from(self.algorithm.as_str());
+        sri.push('-');
+        STANDARD.encode_string(self.as_bytes(), &mut sri);
+        Some(sri)
+    }
+}
+
+impl AsRef<[u8]> for Digest {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl fmt::Debug for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Digest({}:{})", self.algorithm, self.to_hex())
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_hex())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_answers() {
+        let cases = [
+            (
+                Algorithm::Sha256,
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                Algorithm::Sha384,
+                "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded163\
+                 1a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7",
+            ),
+            (
+                Algorithm::Sha512,
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+                 2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+            ),
+        ];
+        for (algorithm, hex) in cases {
+            let digest = algorithm.digest(b"abc");
+            assert_eq!(digest.algorithm(), algorithm);
+            assert_eq!(digest.as_bytes().len(), algorithm.output_len());
+            assert_eq!(digest.to_hex(), hex);
+        }
+    }
+
+    #[cfg(feature = "blake3")]
+    #[test]
+    fn blake3_known_answer() {
+        assert_eq!(
+            Algorithm::Blake3.digest(b"").to_hex(),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+    }
+
+    #[cfg(feature = "xxhash")]
+    #[test]
+    fn xxh3_known_answer() {
+        let digest = Algorithm::Xxh3.digest(b"");
+        assert_eq!(digest.to_hex(), "2d06800538d394c2");
+        assert_eq!(digest.sri(), None);
+    }
+
+    #[test]
+    fn sri() {
+        let digest = Algorithm::Sha384.digest(b"alert('Hello, world.');");
+        assert_eq!(
+            digest.sri().unwrap(),
+            "sha384-H8BRh8j48O9oYatfu5AZzq6A9RINhZO5H16dQZngK7T62em8MUt1FLm52t+eX6xO"
+        );
+    }
+
+    #[test]
+    fn etag() {
+        assert_eq!(
+            Digest::sha256(b"abc").etag(),
+            "\"ba7816bf8f01cfea414140de5dae2223\""
+        );
+    }
+
+    #[test]
+    fn from_bytes_checks_length() {
+        let digest = Digest::sha256(b"abc");
+        assert_eq!(
+            Digest::from_bytes(Algorithm::Sha256, digest.as_bytes()),
+            Some(digest)
+        );
+        assert_eq!(
+            Digest::from_bytes(Algorithm::Sha384, digest.as_bytes()),
+            None
+        );
+    }
+
+    #[test]
+    fn parse_algorithm() {
+        for algorithm in
+            [Algorithm::Sha256, Algorithm::Sha384, Algorithm::Sha512]
+        {
+            assert_eq!(
+                algorithm.as_str().parse::<Algorithm>().ok(),
+                Some(algorithm)
+            );
+        }
+        assert!("md5".parse::<Algorithm>().is_err());
+    }
+}
@@ -0,0 +1,36 @@
+use core::fmt;
+
+/// The encoding applied to the contents of an asset, as used by the HTTP
+/// `Content-Encoding` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[non_exhaustive]
+pub enum ContentEncoding {
+    #[cfg_attr(feature = "serde", serde(rename = "br"))]
+    Brotli,
+    Deflate,
+    Gzip,
+    Zstd,
+}
+
+impl ContentEncoding {
+    /// Returns the name of this encoding as used in HTTP headers.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ContentEncoding::Brotli => "br",
+            ContentEncoding::Deflate => "deflate",
+            ContentEncoding::Gzip => "gzip",
+            ContentEncoding::Zstd => "zstd",
+        }
+    }
+}
+
+impl fmt::Display for ContentEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,172 @@
+use alloc::{borrow::Cow, string::String};
+use core::{convert::Infallible, fmt};
+
+use crate::BoxError;
+
+/// The kind of an [`Error`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Reading or writing the asset failed.
+    Io,
+    /// The contents could not be parsed, e.g. invalid CSS.
+    Parse,
+    /// The contents could not be encoded, e.g. a compressor failed.
+    Encode,
+    /// Any other error.
+    Other,
+}
+
+impl ErrorKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::Io => "io error",
+            ErrorKind::Parse => "parse error",
+            ErrorKind::Encode => "encode error",
+            ErrorKind::Other => "error",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An error that occurred while processing an asset.
+///
+/// Besides its [`ErrorKind`] and underlying cause, an error can carry the key
+/// of the asset and the name of the stage that failed. Stages such as the
+/// `avenue-web` adapters attach their own name, while the key is attached by
+/// whatever knows which asset is being processed.
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    key: Option<String>,
+    stage: Option<Cow<'static, str>>,
+    source: BoxError,
+}
+
+impl Error {
+    pub fn new<E: Into<BoxError>>(kind: ErrorKind, source: E) -> Self {
+        Self {
+            kind,
+            key: None,
+            stage: None,
+            source: source.into(),
+        }
+    }
+
+    /// Creates an [`ErrorKind::Io`] error.
+    #[inline]
+    pub fn io<E: Into<BoxError>>(source: E) -> Self {
+        Self::new(ErrorKind::Io, source)
+    }
+
+    /// Creates an [`ErrorKind::Parse`] error.
+    #[inline]
+    pub fn parse<E: Into<BoxError>>(source: E) -> Self {
+        Self::new(ErrorKind::Parse, source)
+    }
+
+    /// Creates an [`ErrorKind::Encode`] error.
+    #[inline]
+    pub fn encode<E: Into<BoxError>>(source: E) -> Self {
+        Self::new(ErrorKind::Encode, source)
+    }
+
+    /// Converts an arbitrary error into an [`Error`].
+    ///
+    /// If `source` already is an [`Error`], it is returned unchanged so its
+    /// kind, key and stage are preserved. Otherwise it becomes an
+    /// [`ErrorKind::Other`] error, or [`ErrorKind::Io`] for IO errors.
+    pub fn other<E: Into<BoxError>>(source: E) -> Self {
+        let source = source.into();
+        let source = match source.downcast::<Self>() {
+            Ok(err) => return *err,
+            Err(source) => source,
+        };
+        #[cfg(feature = "std")]
+        if source.is::<std::io::Error>() {
+            return Self::io(source);
+        }
+        Self::new(ErrorKind::Other, source)
+    }
+
+    /// Attaches the key of the asset that failed.
+    #[inline]
+    pub fn with_key<K: Into<String>>(mut self, key: K) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Attaches the name of the stage that failed, unless one is already
+    /// attached.
+    #[inline]
+    pub fn with_stage<S: Into<Cow<'static, str>>>(mut self, stage: S) -> Self {
+        if self.stage.is_none() {
+            self.stage = Some(stage.into());
+        }
+        self
+    }
+
+    #[inline]
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns the key of the asset that failed, if known.
+    #[inline]
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// Returns the name of the stage that failed, if known.
+    #[inline]
+    pub fn stage(&self) -> Option<&str> {
+        self.stage.as_deref()
+    }
+
+    /// Consumes the error, returning its underlying cause.
+    #[inline]
+    pub fn into_source(self) -> BoxError {
+        self.source
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.stage, &self.key) {
+            (Some(stage), Some(key)) => {
+                write!(f, "{} in `{}` for `{}`", self.kind, stage, key)?
+            }
+            (Some(stage), None) => write!(f, "{} in `{}`", self.kind, stage)?,
+            (None, Some(key)) => write!(f, "{} for `{}`", self.kind, key)?,
+            (None, None) => write!(f, "{}", self.kind)?,
+        }
+        write!(f, ": {}", self.source)
+    }
+}
+
+impl core::error::Error for Error {
+    // The cause is already part of the `Display` output, so skip over it to
+    // avoid reporting it twice.
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        self.source.source()
+    }
+}
+
+impl From<Infallible> for Error {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    #[inline]
+    fn from(value: std::io::Error) -> Self {
+        Self::io(value)
+    }
+}
@@ -1,4 +1,18 @@
+use alloc::string::String;
+
+use crate::{
+    Algorithm, AndThen, BoxError, ContentEncoding, Digest, Map, Process,
+    ProcessedAsset, Then,
+};
+
 pub trait AssetExt {
+    /// The mime type of the asset.
+    ///
+    /// This is usually guessed from the extension of the key or path, after
+    /// checking the overrides of the [`MimeRegistry`](crate::MimeRegistry).
+    /// Assets with access to their contents fall back to
+    /// [`sniff_mime`](crate::sniff_mime) when the extension is missing or
+    /// unknown.
     #[cfg(feature = "mime")]
     fn mime(&self) -> Option<mime::Mime>;
 
@@ -6,4 +20,148 @@ pub trait AssetExt {
     fn path(&self) -> Option<&std::path::Path>;
 
     fn size_hint(&self) -> Option<usize>;
+
+    /// Returns the encoding applied to the contents, e.g. by a compression
+    /// adapter.
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        None
+    }
+
+    /// Returns when the source of this asset was last modified, e.g. from
+    /// the file metadata of a [`FileAsset`](crate::FileAsset).
+    #[cfg(feature = "std")]
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        None
+    }
+
+    /// Returns a strong HTTP `ETag` derived from the contents, including the
+    /// surrounding quotes.
+    ///
+    /// This is only available when the contents are known without
+    /// processing, e.g. for a [`BufAsset`](crate::BufAsset) or a
+    /// [`ProcessedAsset`]. File assets hash the file on every call, and
+    /// return `None` if it cannot be read. Adapters return `None`, since
+    /// their output is not known until they run.
+    #[inline]
+    fn etag(&self) -> Option<String> {
+        None
+    }
+
+    /// Returns the charset of the contents, if they are text.
+    ///
+    /// The contents are not inspected: all text is assumed to be UTF-8, so
+    /// this returns `"utf-8"` for text mime types as well as JavaScript,
+    /// JSON, XML and SVG. Override this for assets in other encodings.
+    #[cfg(feature = "mime")]
+    fn charset(&self) -> Option<&'static str> {
+        let mime = self.mime()?;
+        let is_text = mime.type_() == mime::TEXT
+            || matches!(
+                mime.subtype().as_str(),
+                "javascript" | "json" | "xml" | "svg"
+            )
+            || matches!(
+                mime.suffix().map(|s| s.as_str()),
+                Some("json" | "xml")
+            );
+        is_text.then_some("utf-8")
+    }
 }
+
+/// Closure-based combinators for any [`Process`].
+///
+/// These make one-off transformations, such as injecting a banner or
+/// replacing a string, possible without defining a new adapter.
+///
+/// ```
+/// use avenue::{BufAsset, Process, ProcessExt};
+///
+/// let asset = BufAsset::new("app.js", &b"console.log(1)"[..])
+///     .map(|contents| [b"/* banner */\n", &contents[..]].concat());
+///
+/// assert_eq!(asset.process_full()?, b"/* banner */\nconsole.log(1)");
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+pub trait ProcessExt: Process {
+    /// Transforms the output with `f`.
+    #[inline]
+    fn map<F, O>(self, f: F) -> Map<Self, F>
+    where
+        Self: Sized,
+        F: FnOnce(Self::Output) -> O,
+        O: AsRef<[u8]>,
+    {
+        Map(self, f)
+    }
+
+    /// Transforms the output with the fallible `f`.
+    #[inline]
+    fn and_then<F, O, E>(self, f: F) -> AndThen<Self, F>
+    where
+        Self: Sized,
+        F: FnOnce(Self::Output) -> Result<O, E>,
+        O: AsRef<[u8]>,
+        E: Into<BoxError>,
+    {
+        AndThen(self, f)
+    }
+
+    /// Passes the result, successful or not, to `f`.
+    ///
+    /// Unlike [`ProcessExt::and_then`], this can be used to recover from
+    /// errors.
+    #[inline]
+    fn then<F, O, E>(self, f: F) -> Then<Self, F>
+    where
+        Self: Sized,
+        F: FnOnce(Result<Self::Output, Self::Error>) -> Result<O, E>,
+        O: AsRef<[u8]>,
+        E: Into<BoxError>,
+    {
+        Then(self, f)
+    }
+
+    /// Processes this asset and computes the digest of its output.
+    ///
+    /// ```
+    /// use avenue::{Algorithm, BufAsset, ProcessExt};
+    ///
+    /// let asset = BufAsset::new("app.js", &b"alert(1)"[..]);
+    /// let (contents, digest) = asset.digest(Algorithm::Sha384)?;
+    /// let integrity = digest.sri().unwrap();
+    ///
+    /// assert_eq!(digest, Algorithm::Sha384.digest(&contents));
+    /// assert!(integrity.starts_with("sha384-"));
+    /// # Ok::<(), core::convert::Infallible>(())
+    /// ```
+    #[inline]
+    fn digest(
+        self,
+        algorithm: Algorithm,
+    ) -> Result<(Self::Output, Digest), Self::Error>
+    where
+        Self: Sized,
+    {
+        let output = self.process_full()?;
+        let digest = algorithm.digest(output.as_ref());
+        Ok((output, digest))
+    }
+
+    /// Processes this asset, keeping its metadata.
+    ///
+    /// See [`ProcessedAsset`].
+    #[inline]
+    fn process_asset<K>(
+        self,
+        key: K,
+    ) -> Result<ProcessedAsset<K, Self::Output>, Self::Error>
+    where
+        Self: Sized + AssetExt,
+    {
+        ProcessedAsset::process(key, self)
+    }
+}
+
+impl<T: Process> ProcessExt for T {}
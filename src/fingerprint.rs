@@ -0,0 +1,248 @@
+use alloc::{format, string::String};
+
+use crate::{Algorithm, BufAsset, Digest, Process, ProcessedAsset};
+
+/// Where a [`Fingerprint`] inserts the hash into a key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum Placement {
+    /// `css/app.css` becomes `css/app.3f9a1c2b.css`.
+    ///
+    /// Keys without an extension get the hash appended, e.g. `LICENSE`
+    /// becomes `LICENSE.3f9a1c2b`.
+    #[default]
+    BeforeExtension,
+    /// `css/app.css` becomes `css/3f9a1c2b.app.css`.
+    Prefix,
+    /// `css/app.css` becomes `css/3f9a1c2b/app.css`.
+    Directory,
+}
+
+/// Rewrites asset keys to include a hash of their processed contents.
+///
+/// Fingerprinted keys change whenever the contents change, so the assets can
+/// be served with long-lived immutable cache headers.
+///
+/// ```
+/// use avenue::{BufAsset, Fingerprint, Placement};
+///
+/// let fingerprint = Fingerprint::new().length(8);
+/// let src = BufAsset::new((), &b"body{}"[..]);
+/// let asset = fingerprint.process("css/app.css", src)?;
+/// assert_eq!(asset.key, "css/app.7c98040a.css");
+///
+/// let fingerprint = fingerprint.placement(Placement::Directory);
+/// let key = fingerprint.key("css/app.css", b"body{}");
+/// assert_eq!(key, "css/7c98040a/app.css");
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fingerprint {
+    algorithm: Algorithm,
+    length: usize,
+    placement: Placement,
+    separator: char,
+}
+
+impl Fingerprint {
+    /// Creates a fingerprint using 8 hex digits of the SHA-256 digest,
+    /// placed before the extension.
+    pub fn new() -> Self {
+        Self {
+            algorithm: Algorithm::Sha256,
+            length: 8,
+            placement: Placement::BeforeExtension,
+            separator: '.',
+        }
+    }
+
+    /// Sets the algorithm used to hash the contents.
+    #[inline]
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets the number of hex digits of the hash to use.
+    ///
+    /// Lengths beyond the hex length of the digest use the whole digest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `length` is zero.
+    #[inline]
+    pub fn length(mut self, length: usize) -> Self {
+        assert!(length > 0, "fingerprint length must be positive");
+        self.length = length;
+        self
+    }
+
+    #[inline]
+    pub fn placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Sets the separator between the hash and the rest of the file name,
+    /// e.g. `'-'` for `app-3f9a1c2b.css`. Defaults to `'.'`.
+    ///
+    /// This is ignored for [`Placement::Directory`].
+    #[inline]
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Returns the fingerprinted version of `key` for `contents`.
+    pub fn key(&self, key: &str, contents: &[u8]) -> String {
+        self.insert_hash(key, &self.algorithm.digest(contents))
+    }
+
+    /// Processes `src`, returning an asset with the fingerprinted `key`.
+    pub fn process<T: Process>(
+        &self,
+        key: &str,
+        src: T,
+    ) -> Result<BufAsset<'static, String>, T::Error> {
+        let contents = src.process_full()?.as_ref().to_vec();
+        Ok(BufAsset::new(self.key(key, &contents), contents))
+    }
+
+    /// Fingerprints the key of a processed asset.
+    ///
+    /// The digest of the asset is reused if it was computed with the same
+    /// algorithm.
+    pub fn apply<K, C>(
+        &self,
+        asset: ProcessedAsset<K, C>,
+    ) -> ProcessedAsset<String, C>
+    where
+        K: AsRef<str>,
+        C: AsRef<[u8]>,
+    {
+        let key = if asset.digest.algorithm() == self.algorithm {
+            self.insert_hash(asset.key.as_ref(), &asset.digest)
+        } else {
+            self.key(asset.key.as_ref(), asset.contents.as_ref())
+        };
+
+        ProcessedAsset {
+            key,
+            contents: asset.contents,
+            #[cfg(feature = "mime")]
+            mime: asset.mime,
+            content_encoding: asset.content_encoding,
+            digest: asset.digest,
+        }
+    }
+
+    fn insert_hash(&self, key: &str, digest: &Digest) -> String {
+        let mut hash = digest.to_hex();
+        hash.truncate(self.length);
+
+        let (dir, name) = match key.rfind('/') {
+            Some(i) => key.split_at(i + 1),
+            None => ("", key),
+        };
+        let sep = self.separator;
+        let name = match self.placement {
+            Placement::BeforeExtension => match name.rfind('.') {
+                Some(i) if i > 0 => {
+                    let (stem, ext) = name.split_at(i);
+                    format!("{stem}{sep}{hash}{ext}")
+                }
+                _ => format!("{name}{sep}{hash}"),
+            },
+            Placement::Prefix => format!("{hash}{sep}{name}"),
+            Placement::Directory => format!("{hash}/{name}"),
+        };
+        format!("{dir}{name}")
+    }
+}
+
+impl Default for Fingerprint {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The SHA-256 digest of `body{}` starts with `7c98040a`.
+    const CONTENTS: &[u8] = b"body{}";
+
+    fn key(fingerprint: &Fingerprint, key: &str) -> String {
+        fingerprint.key(key, CONTENTS)
+    }
+
+    #[test]
+    fn before_extension() {
+        let fingerprint = Fingerprint::new();
+        assert_eq!(key(&fingerprint, "app.css"), "app.7c98040a.css");
+        assert_eq!(key(&fingerprint, "css/app.css"), "css/app.7c98040a.css");
+        assert_eq!(key(&fingerprint, "app.min.css"), "app.min.7c98040a.css");
+        assert_eq!(key(&fingerprint, "a.tar.gz"), "a.tar.7c98040a.gz");
+        assert_eq!(key(&fingerprint, "LICENSE"), "LICENSE.7c98040a");
+        assert_eq!(key(&fingerprint, ".env"), ".env.7c98040a");
+        assert_eq!(key(&fingerprint, "a.b/c"), "a.b/c.7c98040a");
+        assert_eq!(key(&fingerprint, "a.b/.env"), "a.b/.env.7c98040a");
+    }
+
+    #[test]
+    fn prefix() {
+        let fingerprint = Fingerprint::new().placement(Placement::Prefix);
+        assert_eq!(key(&fingerprint, "css/app.css"), "css/7c98040a.app.css");
+        assert_eq!(key(&fingerprint, ".env"), "7c98040a..env");
+
+        let fingerprint = fingerprint.separator('-');
+        assert_eq!(key(&fingerprint, "app.min.css"), "7c98040a-app.min.css");
+    }
+
+    #[test]
+    fn directory() {
+        let fingerprint = Fingerprint::new()
+            .placement(Placement::Directory)
+            .separator('-');
+        assert_eq!(key(&fingerprint, "css/app.css"), "css/7c98040a/app.css");
+        assert_eq!(key(&fingerprint, ".env"), "7c98040a/.env");
+    }
+
+    #[test]
+    fn separator() {
+        let fingerprint = Fingerprint::new().separator('-');
+        assert_eq!(key(&fingerprint, "app.css"), "app-7c98040a.css");
+        assert_eq!(key(&fingerprint, "LICENSE"), "LICENSE-7c98040a");
+    }
+
+    #[test]
+    fn length_is_capped_at_digest() {
+        let full = Digest::sha256(CONTENTS).to_hex();
+        let fingerprint = Fingerprint::new().length(100);
+        assert_eq!(key(&fingerprint, "app.css"), format!("app.{full}.css"));
+
+        let fingerprint = Fingerprint::new().length(1);
+        assert_eq!(key(&fingerprint, "app.css"), "app.7.css");
+    }
+
+    #[test]
+    #[should_panic = "fingerprint length must be positive"]
+    fn zero_length_panics() {
+        let _ = Fingerprint::new().length(0);
+    }
+
+    #[test]
+    fn apply_reuses_digest() {
+        let src = BufAsset::new("app.css", CONTENTS);
+        let asset = ProcessedAsset::process("app.css", src).unwrap();
+        let asset = Fingerprint::new().apply(asset);
+        assert_eq!(asset.key, "app.7c98040a.css");
+
+        let asset =
+            Fingerprint::new().algorithm(Algorithm::Sha384).apply(asset);
+        let hash = &Algorithm::Sha384.digest(CONTENTS).to_hex()[..8];
+        assert_eq!(asset.key, format!("app.7c98040a.{hash}.css"));
+    }
+}
@@ -1,19 +1,113 @@
 #![no_std]
 
+#[cfg(any(feature = "tar", feature = "zip"))]
+mod archive;
 mod asset;
+mod batch;
+#[cfg(feature = "std")]
+mod cache;
+mod combinator;
+mod digest;
+mod encoding;
+mod error;
 mod ext;
+mod fingerprint;
+#[cfg(feature = "manifest")]
+mod manifest;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod pipeline;
+mod processed;
+mod read;
+#[cfg(feature = "mime")]
+mod registry;
+#[cfg(feature = "fs")]
+mod scan;
+#[cfg(feature = "serde")]
+mod serde_impls;
+#[cfg(feature = "std")]
+mod set;
+mod sink;
+mod sniff;
+mod source;
 
+#[cfg(feature = "tar")]
+pub use archive::TarSink;
+#[cfg(feature = "zip")]
+pub use archive::{ZipSink, ZipSource};
 pub use asset::{Asset, BufAsset, FileAsset};
-pub use ext::AssetExt;
+pub use batch::{map_batch, process_batch};
+#[cfg(feature = "std")]
+pub use cache::{Cache, Cached, CachedStage};
+pub use combinator::{AndThen, Map, Then};
+pub use digest::{Algorithm, Digest};
+pub use encoding::ContentEncoding;
+pub use error::{Error, ErrorKind};
+pub use ext::{AssetExt, ProcessExt};
+pub use fingerprint::{Fingerprint, Placement};
+#[cfg(feature = "manifest")]
+pub use manifest::{Manifest, ManifestEntry};
+#[cfg(feature = "mmap")]
+pub use mmap::MmapAsset;
+pub use pipeline::{from_fn, FnStage, Pipeline, Stage};
+pub use processed::ProcessedAsset;
+pub use read::{AssetReader, ReadBuf};
+#[cfg(feature = "std")]
+pub use read::{FromIoRead, IoRead};
+#[cfg(feature = "mime")]
+pub use registry::MimeRegistry;
+#[cfg(feature = "fs")]
+pub use scan::Scan;
+#[cfg(feature = "std")]
+pub use set::AssetSet;
+pub use sink::AssetSink;
+#[cfg(feature = "std")]
+pub use sink::DirSink;
+#[cfg(feature = "mime")]
+pub use sniff::{mime_prefer_sniffed, sniff_mime};
+pub use sniff::{sniff, SNIFF_LEN};
+#[cfg(feature = "fs")]
+pub use source::DirSource;
+pub use source::{AssetSource, MemorySource, Overlay};
 
 extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
 use alloc::{boxed::Box, string::String, vec::Vec};
-use core::{convert::Infallible, error::Error};
+use core::{convert::Infallible, future::Future};
 
-pub type BoxError = Box<dyn Error>;
+/// Items used by code generated by `avenue-macros`. Not public API.
+#[cfg(feature = "embed")]
+#[doc(hidden)]
+pub mod __private {
+    pub use phf;
+    pub use std::path::Path;
+}
+
+/// Expands the lookup functions generated by `#[embed]`, which need the
+/// `embed` feature. Not public API.
+#[cfg(feature = "embed")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __embed_lookup {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+#[cfg(not(feature = "embed"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __embed_lookup {
+    ($($item:tt)*) => {
+        ::core::compile_error!(
+            "`#[embed]` requires the `embed` feature of `avenue`"
+        );
+    };
+}
+
+pub type BoxError = Box<dyn core::error::Error + Send + Sync + 'static>;
 
 pub trait Process {
     type Error: Into<BoxError>;
@@ -28,6 +122,31 @@ pub trait ProcessRead {
     fn process_read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error>;
 }
 
+/// Conversion into a [`ProcessRead`] stream.
+///
+/// This is the streaming counterpart of [`Process`]: instead of producing the
+/// whole output at once, the returned reader yields it in chunks, so large
+/// assets can be processed with bounded memory.
+pub trait IntoProcessRead {
+    type Error: Into<BoxError>;
+    type Reader: ProcessRead;
+
+    fn into_process_read(self) -> Result<Self::Reader, Self::Error>;
+}
+
+/// The async counterpart of [`Process`].
+///
+/// Implementations should not block the executor, e.g. [`FileAsset`] reads
+/// its contents using tokio's non-blocking file IO.
+pub trait ProcessAsync {
+    type Error: Into<BoxError>;
+    type Output: AsRef<[u8]>;
+
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send;
+}
+
 macro_rules! impl_move {
     ($($ty:ty)+) => {
         $(
@@ -40,12 +159,99 @@ macro_rules! impl_move {
                     Ok(self)
                 }
             }
+
+            impl ProcessAsync for $ty {
+                type Error = Infallible;
+                type Output = Self;
+
+                #[inline]
+                fn process_async(
+                    self,
+                ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send
+                {
+                    core::future::ready(Ok(self))
+                }
+            }
         )+
     }
 }
 
 impl_move!(String &str Vec<u8> &[u8]);
 
+impl IntoProcessRead for String {
+    type Error = Infallible;
+    type Reader = ReadBuf<'static>;
+
+    #[inline]
+    fn into_process_read(self) -> Result<Self::Reader, Self::Error> {
+        Ok(ReadBuf::new(self.into_bytes()))
+    }
+}
+
+impl IntoProcessRead for Vec<u8> {
+    type Error = Infallible;
+    type Reader = ReadBuf<'static>;
+
+    #[inline]
+    fn into_process_read(self) -> Result<Self::Reader, Self::Error> {
+        Ok(ReadBuf::new(self))
+    }
+}
+
+impl<'a> IntoProcessRead for &'a str {
+    type Error = Infallible;
+    type Reader = &'a [u8];
+
+    #[inline]
+    fn into_process_read(self) -> Result<Self::Reader, Self::Error> {
+        Ok(self.as_bytes())
+    }
+}
+
+impl<'a> IntoProcessRead for &'a [u8] {
+    type Error = Infallible;
+    type Reader = &'a [u8];
+
+    #[inline]
+    fn into_process_read(self) -> Result<Self::Reader, Self::Error> {
+        Ok(self)
+    }
+}
+
+impl ProcessRead for &[u8] {
+    type Error = Infallible;
+
+    #[inline]
+    fn process_read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let n = buf.len().min(self.len());
+        let (head, tail) = self.split_at(n);
+        buf[..n].copy_from_slice(head);
+        *self = tail;
+        Ok(n)
+    }
+}
+
+impl<T: ProcessAsync> ProcessAsync for Box<T> {
+    type Error = T::Error;
+    type Output = T::Output;
+
+    #[inline]
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        T::process_async(*self)
+    }
+}
+
+impl<T: ProcessRead + ?Sized> ProcessRead for Box<T> {
+    type Error = T::Error;
+
+    #[inline]
+    fn process_read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        T::process_read(self, buf)
+    }
+}
+
 impl<T: Process> Process for Box<T> {
     type Error = T::Error;
     type Output = T::Output;
@@ -69,19 +275,29 @@ impl Process for std::fs::File {
     }
 }
 
+#[cfg(feature = "std")]
+impl ProcessRead for std::fs::File {
+    type Error = std::io::Error;
+
+    #[inline]
+    fn process_read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        std::io::Read::read(self, buf)
+    }
+}
+
 #[cfg(feature = "either")]
 impl<L: Process, R: Process> Process for either::Either<L, R> {
-    type Error = BoxError;
+    type Error = Error;
     type Output = either::Either<L::Output, R::Output>;
 
     #[inline]
     fn process_full(self) -> Result<Self::Output, Self::Error> {
         match self {
             Self::Left(left) => Ok(either::Either::Left(
-                left.process_full().map_err(Into::into)?,
+                left.process_full().map_err(Error::other)?,
             )),
             Self::Right(right) => Ok(either::Either::Right(
-                right.process_full().map_err(Into::into)?,
+                right.process_full().map_err(Error::other)?,
             )),
         }
     }
@@ -105,4 +321,20 @@ impl<L: AssetExt, R: AssetExt> AssetExt for either::Either<L, R> {
     fn size_hint(&self) -> Option<usize> {
         either::for_both!(*self, ref inner => inner.size_hint())
     }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        either::for_both!(*self, ref inner => inner.content_encoding())
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        either::for_both!(*self, ref inner => inner.last_modified())
+    }
+
+    #[inline]
+    fn etag(&self) -> Option<String> {
+        either::for_both!(*self, ref inner => inner.etag())
+    }
 }
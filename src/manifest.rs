@@ -0,0 +1,315 @@
+use alloc::{
+    collections::{btree_map, BTreeMap},
+    string::String,
+    vec::Vec,
+};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ContentEncoding, Digest, Error, ProcessedAsset};
+
+/// A mapping from the logical keys of assets to their output keys, e.g. from
+/// `main.js` to `main.3f9a1c2b.js`, along with their metadata.
+///
+/// The JSON form is compatible with Vite's `manifest.json`, with additional
+/// fields for the metadata. A flat `{ "key": "output key" }` form, as written
+/// by webpack's manifest plugin, is available through
+/// [`to_flat_json`](Manifest::to_flat_json).
+///
+/// ```no_run
+/// use avenue::{BufAsset, Fingerprint, Manifest, ProcessExt};
+///
+/// let fingerprint = Fingerprint::new();
+/// let src = BufAsset::new("main.js", &b"console.log(1)"[..]);
+///
+/// let mut manifest = Manifest::new();
+/// let asset = fingerprint.apply(src.process_asset("main.js")?);
+/// manifest.insert_processed("main.js", &asset).is_entry = true;
+/// manifest.write("dist/manifest.json")?;
+/// # Ok::<(), avenue::Error>(())
+/// ```
+///
+/// ```json
+/// {
+///   "main.js": {
+///     "file": "main.3f9a1c2b.js",
+///     "src": "main.js",
+///     "isEntry": true,
+///     "size": 1024,
+///     "digest": "sha256:3f9a1c2b...",
+///     "mime": "text/javascript"
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Manifest {
+    entries: BTreeMap<String, ManifestEntry>,
+}
+
+/// An asset of a [`Manifest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+    /// The output key of the asset.
+    pub file: String,
+    /// The logical key of the asset.
+    pub src: String,
+    /// Whether this asset is an entry point, such as a page script.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub is_entry: bool,
+    /// The size of the output, in bytes.
+    pub size: u64,
+    /// The digest of the output.
+    pub digest: Digest,
+    /// The mime type of the asset before any content encoding.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::serde_impls::mime_opt"
+    )]
+    pub mime: Option<mime::Mime>,
+    /// The content encodings the asset is available in, e.g. when
+    /// precompressed variants are served alongside it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub encodings: Vec<ContentEncoding>,
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
+impl ManifestEntry {
+    /// Creates an entry for `contents` written under `file`.
+    pub fn new<S, F>(src: S, file: F, contents: &[u8]) -> Self
+    where
+        S: Into<String>,
+        F: Into<String>,
+    {
+        Self {
+            file: file.into(),
+            src: src.into(),
+            is_entry: false,
+            size: contents.len() as u64,
+            digest: Digest::sha256(contents),
+            mime: None,
+            encodings: Vec::new(),
+        }
+    }
+}
+
+impl Manifest {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts an entry under its logical key, returning the entry it
+    /// replaced.
+    pub fn insert(&mut self, entry: ManifestEntry) -> Option<ManifestEntry> {
+        self.entries.insert(entry.src.clone(), entry)
+    }
+
+    /// Inserts an entry for a processed asset under the logical key `src`,
+    /// using the key of the asset as the output key.
+    pub fn insert_processed<S, K, C>(
+        &mut self,
+        src: S,
+        asset: &ProcessedAsset<K, C>,
+    ) -> &mut ManifestEntry
+    where
+        S: Into<String>,
+        K: AsRef<str>,
+        C: AsRef<[u8]>,
+    {
+        let entry = ManifestEntry {
+            file: String::from(asset.key.as_ref()),
+            src: src.into(),
+            is_entry: false,
+            size: asset.contents.as_ref().len() as u64,
+            digest: asset.digest,
+            mime: asset.mime.clone(),
+            encodings: asset.content_encoding.into_iter().collect(),
+        };
+        match self.entries.entry(entry.src.clone()) {
+            btree_map::Entry::Occupied(mut e) => {
+                e.insert(entry);
+                e.into_mut()
+            }
+            btree_map::Entry::Vacant(e) => e.insert(entry),
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, src: &str) -> Option<&ManifestEntry> {
+        self.entries.get(src)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, src: &str) -> Option<&mut ManifestEntry> {
+        self.entries.get_mut(src)
+    }
+
+    #[inline]
+    pub fn remove(&mut self, src: &str) -> Option<ManifestEntry> {
+        self.entries.remove(src)
+    }
+
+    /// Returns the output key of the asset with the logical key `src`.
+    #[inline]
+    pub fn file(&self, src: &str) -> Option<&str> {
+        self.get(src).map(|entry| entry.file.as_str())
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> btree_map::Iter<'_, String, ManifestEntry> {
+        self.entries.iter()
+    }
+
+    /// Parses a manifest in the Vite-like form written by
+    /// [`to_json`](Manifest::to_json).
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(Error::parse)
+    }
+
+    /// Serializes this manifest as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string_pretty(self).map_err(Error::encode)
+    }
+
+    /// Serializes this manifest as a flat JSON object mapping logical keys
+    /// to output keys, as written by webpack's manifest plugin.
+    pub fn to_flat_json(&self) -> Result<String, Error> {
+        let flat: BTreeMap<&str, &str> = self
+            .iter()
+            .map(|(src, entry)| (src.as_str(), entry.file.as_str()))
+            .collect();
+        serde_json::to_string_pretty(&flat).map_err(Error::encode)
+    }
+
+    /// Writes the JSON form of this manifest to `path`.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let json = self.to_json()?;
+        std::fs::write(path, json).map_err(Error::io)
+    }
+}
+
+impl<'a> IntoIterator for &'a Manifest {
+    type IntoIter = btree_map::Iter<'a, String, ManifestEntry>;
+    type Item = (&'a String, &'a ManifestEntry);
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FromIterator<ManifestEntry> for Manifest {
+    fn from_iter<I: IntoIterator<Item = ManifestEntry>>(iter: I) -> Self {
+        let mut manifest = Self::new();
+        manifest.extend(iter);
+        manifest
+    }
+}
+
+impl Extend<ManifestEntry> for Manifest {
+    fn extend<I: IntoIterator<Item = ManifestEntry>>(&mut self, iter: I) {
+        for entry in iter {
+            self.insert(entry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+
+    use super::*;
+
+    fn manifest() -> Manifest {
+        let css = ProcessedAsset {
+            key: "css/app.1.css",
+            contents: &b"body{}"[..],
+            mime: Some(mime::TEXT_CSS),
+            content_encoding: Some(ContentEncoding::Brotli),
+            digest: Digest::sha256(b"body{}"),
+        };
+        let mut manifest = Manifest::new();
+        manifest.insert_processed("css/app.css", &css);
+        let mut js = ManifestEntry::new("main.js", "main.2.js", b"1");
+        js.is_entry = true;
+        manifest.insert(js);
+        manifest
+    }
+
+    #[test]
+    fn to_json() {
+        let css = Digest::sha256(b"body{}").to_hex();
+        let js = Digest::sha256(b"1").to_hex();
+        let expected = format!(
+            r#"{{
+  "css/app.css": {{
+    "file": "css/app.1.css",
+    "src": "css/app.css",
+    "size": 6,
+    "digest": "sha256:{css}",
+    "mime": "text/css",
+    "encodings": [
+      "br"
+    ]
+  }},
+  "main.js": {{
+    "file": "main.2.js",
+    "src": "main.js",
+    "isEntry": true,
+    "size": 1,
+    "digest": "sha256:{js}"
+  }}
+}}"#
+        );
+        assert_eq!(manifest().to_json().unwrap(), expected);
+    }
+
+    #[test]
+    fn from_json_round_trip() {
+        let manifest = manifest();
+        let json = manifest.to_json().unwrap();
+        assert_eq!(Manifest::from_json(&json).unwrap(), manifest);
+
+        // `isEntry`, `mime` and `encodings` are optional.
+        let digest = Digest::sha256(b"").to_hex();
+        let json = format!(
+            r#"{{"a.js": {{"file": "a.1.js", "src": "a.js", "size": 0,
+                "digest": "sha256:{digest}"}}}}"#
+        );
+        assert_eq!(
+            Manifest::from_json(&json).unwrap().get("a.js"),
+            Some(&ManifestEntry::new("a.js", "a.1.js", b""))
+        );
+
+        let missing = r#"{"a.js": {"file": "a.1.js"}}"#;
+        assert!(Manifest::from_json(missing).is_err());
+    }
+
+    #[test]
+    fn to_flat_json() {
+        assert_eq!(
+            manifest().to_flat_json().unwrap(),
+            r#"{
+  "css/app.css": "css/app.1.css",
+  "main.js": "main.2.js"
+}"#
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use alloc::{borrow::Cow, string::String};
+#[cfg(feature = "tokio")]
+use core::future::Future;
+use std::{fs::File, io, path::Path};
+
+use memmap2::Mmap;
+
+#[cfg(feature = "tokio")]
+use crate::ProcessAsync;
+use crate::{AssetExt, Digest, FileAsset, Process};
+
+/// A file asset whose contents are memory-mapped instead of read.
+///
+/// [`process_full`](Process::process_full) returns a zero-copy view of the
+/// file, so large assets such as fonts, videos or wasm modules are not
+/// copied onto the heap, and the pages can be shared between processes.
+///
+/// The file must not be modified or truncated while it is mapped, otherwise
+/// the contents may change underneath the view or the process may crash.
+/// This is why construction is `unsafe`, see [`MmapAsset::new`].
+#[derive(Debug, Clone)]
+pub struct MmapAsset<'p, K> {
+    /// A key identifying this asset.
+    ///
+    /// This will typically be a relative logical path for the asset, but it
+    /// could be anything.
+    pub key: K,
+    /// A path to an existing file, covered by the contract of
+    /// [`MmapAsset::new`].
+    path: Cow<'p, Path>,
+}
+
+impl<'p, K> MmapAsset<'p, K> {
+    /// Creates an asset mapping the file at `path`.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this or any other
+    /// process, while this asset or any output it returned is alive.
+    /// Otherwise reading the mapped contents is undefined behavior.
+    #[inline]
+    pub unsafe fn new<P: Into<Cow<'p, Path>>>(key: K, path: P) -> Self {
+        Self {
+            key,
+            path: path.into(),
+        }
+    }
+
+    /// Creates an asset mapping the file of `asset`.
+    ///
+    /// # Safety
+    ///
+    /// See [`MmapAsset::new`].
+    #[inline]
+    pub unsafe fn from_file(asset: FileAsset<'p, K>) -> Self {
+        // SAFETY: Upheld by the caller.
+        unsafe { Self::new(asset.key, asset.path) }
+    }
+}
+
+/// Maps the file at `path`.
+///
+/// # Safety
+///
+/// The file must not be modified while the map is alive.
+unsafe fn map(path: &Path) -> io::Result<Mmap> {
+    let file = File::open(path)?;
+    // SAFETY: Upheld by the caller.
+    unsafe { Mmap::map(&file) }
+}
+
+impl<K> Process for MmapAsset<'_, K> {
+    type Error = io::Error;
+    type Output = Mmap;
+
+    fn process_full(self) -> Result<Self::Output, Self::Error> {
+        // SAFETY: The caller of `MmapAsset::new` promised that the file is
+        // not modified while the asset or its output is alive.
+        unsafe { map(&self.path) }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<K> ProcessAsync for MmapAsset<'_, K> {
+    type Error = io::Error;
+    type Output = Mmap;
+
+    /// Maps the file on the blocking thread pool.
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        let path = self.path.into_owned();
+        async move {
+            // SAFETY: The caller of `MmapAsset::new` promised that the file
+            // is not modified while the asset or its output is alive.
+            tokio::task::spawn_blocking(move || unsafe { map(&path) })
+                .await
+                .map_err(io::Error::other)?
+        }
+    }
+}
+
+#[cfg(feature = "mime")]
+impl<K: AsRef<Path>> MmapAsset<'_, K> {
+    /// Like [`AssetExt::mime`], but checks the
+    /// [`MimeRegistry`](crate::MimeRegistry) override for the key first.
+    #[inline]
+    pub fn mime_with_key(&self) -> Option<mime::Mime> {
+        crate::registry::guess_mime(Some(self.key.as_ref()), &self.path)
+            .or_else(|| crate::sniff::sniff_file(&self.path))
+    }
+}
+
+impl<K> AssetExt for MmapAsset<'_, K> {
+    #[cfg(feature = "mime")]
+    #[inline]
+    fn mime(&self) -> Option<mime::Mime> {
+        crate::registry::guess_mime(None, &self.path)
+            .or_else(|| crate::sniff::sniff_file(&self.path))
+    }
+
+    #[inline]
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        std::fs::metadata(&self.path).map(|m| m.len() as usize).ok()
+    }
+
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        std::fs::metadata(&self.path)
+            .and_then(|m| m.modified())
+            .ok()
+    }
+
+    #[inline]
+    fn etag(&self) -> Option<String> {
+        Digest::sha256_file(&self.path)
+            .ok()
+            .map(|digest| digest.etag())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_file_contents() {
+        let path = std::env::temp_dir()
+            .join(alloc::format!("avenue-mmap-{}", std::process::id()));
+        std::fs::write(&path, b"hello").unwrap();
+
+        // SAFETY: Nothing else knows about this file.
+        let asset = unsafe { MmapAsset::new("hello.txt", path.as_path()) };
+        assert_eq!(&asset.process_full().unwrap()[..], b"hello");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
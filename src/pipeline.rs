@@ -0,0 +1,245 @@
+use alloc::{boxed::Box, string::String, sync::Arc, vec::Vec};
+use core::fmt;
+
+use crate::{Error, Process};
+
+/// A processing step that can be chosen at runtime.
+///
+/// Unlike adapters such as those in `avenue-web`, which are composed at the
+/// type level, stages are object safe so they can be collected into a
+/// [`Pipeline`] from configuration, command-line flags or per-asset rules.
+pub trait Stage: Send + Sync {
+    /// The name of this stage, which is attached to its errors.
+    fn name(&self) -> &str;
+
+    /// Transforms the contents of the asset identified by `key`.
+    fn apply(&self, key: &str, contents: Vec<u8>) -> Result<Vec<u8>, Error>;
+
+    /// Returns a stable description of this stage and its configuration.
+    ///
+    /// This is used to invalidate cached outputs, so it should change
+    /// whenever the output for the same input would. Defaults to the name.
+    fn fingerprint(&self) -> String {
+        String::from(self.name())
+    }
+}
+
+impl<S: Stage + ?Sized> Stage for Box<S> {
+    #[inline]
+    fn name(&self) -> &str {
+        S::name(self)
+    }
+
+    #[inline]
+    fn apply(&self, key: &str, contents: Vec<u8>) -> Result<Vec<u8>, Error> {
+        S::apply(self, key, contents)
+    }
+
+    #[inline]
+    fn fingerprint(&self) -> String {
+        S::fingerprint(self)
+    }
+}
+
+impl<S: Stage + ?Sized> Stage for Arc<S> {
+    #[inline]
+    fn name(&self) -> &str {
+        S::name(self)
+    }
+
+    #[inline]
+    fn apply(&self, key: &str, contents: Vec<u8>) -> Result<Vec<u8>, Error> {
+        S::apply(self, key, contents)
+    }
+
+    #[inline]
+    fn fingerprint(&self) -> String {
+        S::fingerprint(self)
+    }
+}
+
+/// A [`Stage`] backed by a closure.
+///
+/// Created by [`from_fn`]. Its fingerprint is only its name, so the name
+/// should be changed whenever the closure does.
+#[derive(Clone)]
+pub struct FnStage<F> {
+    name: &'static str,
+    f: F,
+}
+
+/// Creates a [`Stage`] named `name` that runs `f`.
+///
+/// ```
+/// use avenue::Stage;
+///
+/// let banner = avenue::from_fn("banner", |_key, contents| {
+///     Ok([b"/* banner */\n", &contents[..]].concat())
+/// });
+///
+/// let out = banner.apply("app.js", b"console.log(1)".to_vec())?;
+/// assert_eq!(out, b"/* banner */\nconsole.log(1)");
+/// # Ok::<(), avenue::Error>(())
+/// ```
+#[inline]
+pub fn from_fn<F>(name: &'static str, f: F) -> FnStage<F>
+where
+    F: Fn(&str, Vec<u8>) -> Result<Vec<u8>, Error> + Send + Sync,
+{
+    FnStage { name, f }
+}
+
+impl<F> Stage for FnStage<F>
+where
+    F: Fn(&str, Vec<u8>) -> Result<Vec<u8>, Error> + Send + Sync,
+{
+    #[inline]
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    #[inline]
+    fn apply(&self, key: &str, contents: Vec<u8>) -> Result<Vec<u8>, Error> {
+        (self.f)(key, contents)
+    }
+}
+
+impl<F> fmt::Debug for FnStage<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnStage").field("name", &self.name).finish()
+    }
+}
+
+/// A sequence of [`Stage`]s built at runtime.
+///
+/// Stages run in the order they were added, each receiving the output of the
+/// previous one. Errors are tagged with the name of the failing stage. A
+/// pipeline is itself a stage, so pipelines can be nested.
+///
+/// ```ignore
+/// use avenue_web::WebStage;
+///
+/// let mut pipeline = Pipeline::new();
+/// for name in ["minify_css", "compress_brotli"] {
+///     pipeline.push(name.parse::<WebStage>()?);
+/// }
+/// let out = pipeline.process("style.css", asset)?;
+/// ```
+#[derive(Default)]
+pub struct Pipeline {
+    stages: Vec<Box<dyn Stage>>,
+}
+
+impl Pipeline {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a stage, returning the pipeline for chaining.
+    #[inline]
+    pub fn stage<S: Stage + 'static>(mut self, stage: S) -> Self {
+        self.push(stage);
+        self
+    }
+
+    /// Appends a stage.
+    #[inline]
+    pub fn push<S: Stage + 'static>(&mut self, stage: S) {
+        self.stages.push(Box::new(stage));
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Returns the stages of this pipeline in order.
+    #[inline]
+    pub fn stages(&self) -> &[Box<dyn Stage>] {
+        &self.stages
+    }
+
+    /// Runs every stage over `contents`.
+    pub fn run(&self, key: &str, contents: Vec<u8>) -> Result<Vec<u8>, Error> {
+        self.stages.iter().try_fold(contents, |contents, stage| {
+            stage
+                .apply(key, contents)
+                .map_err(|err| err.with_stage(String::from(stage.name())))
+        })
+    }
+
+    /// Processes `src` and runs every stage over the output.
+    ///
+    /// Errors are tagged with `key`.
+    pub fn process<T: Process>(
+        &self,
+        key: &str,
+        src: T,
+    ) -> Result<Vec<u8>, Error> {
+        src.process_full()
+            .map_err(Error::other)
+            .and_then(|out| self.run(key, out.as_ref().to_vec()))
+            .map_err(|err| err.with_key(key))
+    }
+}
+
+impl Stage for Pipeline {
+    #[inline]
+    fn name(&self) -> &str {
+        "pipeline"
+    }
+
+    #[inline]
+    fn apply(&self, key: &str, contents: Vec<u8>) -> Result<Vec<u8>, Error> {
+        self.run(key, contents)
+    }
+
+    fn fingerprint(&self) -> String {
+        let mut fingerprint = String::from("[");
+        for (i, stage) in self.stages.iter().enumerate() {
+            if i > 0 {
+                fingerprint.push(',');
+            }
+            fingerprint.push_str(&stage.fingerprint());
+        }
+        fingerprint.push(']');
+        fingerprint
+    }
+}
+
+impl fmt::Debug for Pipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.stages.iter().map(|stage| stage.name()))
+            .finish()
+    }
+}
+
+impl From<Vec<Box<dyn Stage>>> for Pipeline {
+    #[inline]
+    fn from(stages: Vec<Box<dyn Stage>>) -> Self {
+        Self { stages }
+    }
+}
+
+impl FromIterator<Box<dyn Stage>> for Pipeline {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = Box<dyn Stage>>>(iter: I) -> Self {
+        Self {
+            stages: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Box<dyn Stage>> for Pipeline {
+    #[inline]
+    fn extend<I: IntoIterator<Item = Box<dyn Stage>>>(&mut self, iter: I) {
+        self.stages.extend(iter);
+    }
+}
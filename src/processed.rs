@@ -0,0 +1,117 @@
+use alloc::string::String;
+use core::convert::Infallible;
+
+use crate::{AssetExt, ContentEncoding, Digest, Process};
+
+/// The output of a process along with the metadata of the asset.
+///
+/// Unlike the raw output of [`Process::process_full`], this keeps the key,
+/// mime type and content encoding of the asset, which is what is needed to
+/// set the correct HTTP headers when serving it.
+///
+/// ```ignore
+/// let asset = FileAsset::new("style.css", "assets/style.css")
+///     .minify_css()
+///     .compress_brotli()
+///     .process_asset("style.css")?;
+///
+/// assert_eq!(asset.content_encoding, Some(ContentEncoding::Brotli));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProcessedAsset<K, C> {
+    /// A key identifying this asset.
+    pub key: K,
+    /// The processed contents.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            with = "crate::serde_impls::bytes",
+            bound(
+                serialize = "C: AsRef<[u8]>",
+                deserialize = "C: From<alloc::vec::Vec<u8>>"
+            )
+        )
+    )]
+    pub contents: C,
+    /// The mime type of the asset before any content encoding.
+    #[cfg(feature = "mime")]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_impls::mime_opt")
+    )]
+    pub mime: Option<mime::Mime>,
+    /// The encoding applied to the contents, if any.
+    pub content_encoding: Option<ContentEncoding>,
+    /// The digest of the processed contents.
+    pub digest: Digest,
+}
+
+impl<K, C: AsRef<[u8]>> ProcessedAsset<K, C> {
+    /// Processes `src`, capturing its metadata.
+    pub fn process<T>(key: K, src: T) -> Result<Self, T::Error>
+    where
+        T: Process<Output = C> + AssetExt,
+    {
+        #[cfg(feature = "mime")]
+        let mime = src.mime();
+        let content_encoding = src.content_encoding();
+        let contents = src.process_full()?;
+        let digest = Digest::sha256(contents.as_ref());
+
+        Ok(Self {
+            key,
+            contents,
+            #[cfg(feature = "mime")]
+            mime,
+            content_encoding,
+            digest,
+        })
+    }
+}
+
+impl<K, C: AsRef<[u8]>> AsRef<[u8]> for ProcessedAsset<K, C> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.contents.as_ref()
+    }
+}
+
+impl<K, C: AsRef<[u8]>> Process for ProcessedAsset<K, C> {
+    type Error = Infallible;
+    type Output = C;
+
+    #[inline]
+    fn process_full(self) -> Result<Self::Output, Self::Error> {
+        Ok(self.contents)
+    }
+}
+
+impl<K, C: AsRef<[u8]>> AssetExt for ProcessedAsset<K, C> {
+    #[cfg(feature = "mime")]
+    #[inline]
+    fn mime(&self) -> Option<mime::Mime> {
+        self.mime.clone()
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn path(&self) -> Option<&std::path::Path> {
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.contents.as_ref().len())
+    }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        self.content_encoding
+    }
+
+    #[inline]
+    fn etag(&self) -> Option<String> {
+        Some(self.digest.etag())
+    }
+}
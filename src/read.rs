@@ -0,0 +1,98 @@
+use alloc::borrow::Cow;
+use core::convert::Infallible;
+
+use crate::{Error, ProcessRead};
+
+/// A [`ProcessRead`] stream over an in-memory buffer.
+#[derive(Debug, Clone)]
+pub struct ReadBuf<'c> {
+    contents: Cow<'c, [u8]>,
+    pos: usize,
+}
+
+impl<'c> ReadBuf<'c> {
+    #[inline]
+    pub fn new<C: Into<Cow<'c, [u8]>>>(contents: C) -> Self {
+        Self {
+            contents: contents.into(),
+            pos: 0,
+        }
+    }
+
+    /// Returns the bytes that have not been read yet.
+    #[inline]
+    pub fn remaining(&self) -> &[u8] {
+        &self.contents[self.pos..]
+    }
+}
+
+impl ProcessRead for ReadBuf<'_> {
+    type Error = Infallible;
+
+    #[inline]
+    fn process_read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        let n = self.remaining().process_read(buf)?;
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+/// The [`ProcessRead`] stream of an [`Asset`](crate::Asset).
+#[derive(Debug)]
+pub enum AssetReader<'c> {
+    Buf(ReadBuf<'c>),
+    #[cfg(feature = "std")]
+    File(std::fs::File),
+}
+
+impl ProcessRead for AssetReader<'_> {
+    type Error = Error;
+
+    #[inline]
+    fn process_read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        match self {
+            AssetReader::Buf(reader) => {
+                reader.process_read(buf).map_err(Into::into)
+            }
+            #[cfg(feature = "std")]
+            AssetReader::File(reader) => {
+                reader.process_read(buf).map_err(Error::io)
+            }
+        }
+    }
+}
+
+/// Adapts a [`ProcessRead`] stream to [`std::io::Read`].
+///
+/// This makes it possible to feed a stream into anything that consumes
+/// readers, such as streaming encoders or [`std::io::copy`].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IoRead<R>(pub R);
+
+#[cfg(feature = "std")]
+impl<R: ProcessRead> std::io::Read for IoRead<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.process_read(buf).map_err(|err| {
+            match err.into().downcast::<std::io::Error>() {
+                Ok(err) => *err,
+                Err(err) => std::io::Error::other(err),
+            }
+        })
+    }
+}
+
+/// Adapts a [`std::io::Read`] to a [`ProcessRead`] stream.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct FromIoRead<R>(pub R);
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> ProcessRead for FromIoRead<R> {
+    type Error = std::io::Error;
+
+    #[inline]
+    fn process_read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.0.read(buf)
+    }
+}
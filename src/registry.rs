@@ -0,0 +1,153 @@
+use alloc::{collections::BTreeMap, string::String};
+use std::{
+    path::Path,
+    sync::{PoisonError, RwLock},
+};
+
+use mime::Mime;
+
+static GLOBAL: RwLock<MimeRegistry> = RwLock::new(MimeRegistry::new());
+
+/// User-defined mime types, taking precedence over the types guessed from
+/// file extensions.
+///
+/// Overrides can be set for an extension or for a single key. Assets
+/// consult the [global](MimeRegistry::global) registry in
+/// [`AssetExt::mime`](crate::AssetExt::mime), before falling back to
+/// [`mime_guess`] and content sniffing. Key overrides only apply to assets
+/// whose key is path-like; file assets look them up in
+/// [`FileAsset::mime_with_key`](crate::FileAsset::mime_with_key).
+///
+/// ```
+/// use avenue::{AssetExt, BufAsset, MimeRegistry};
+///
+/// MimeRegistry::new()
+///     .extension("webmanifest", "application/manifest+json".parse()?)
+///     .extension("tpl", mime::TEXT_HTML)
+///     .key("LICENSE", mime::TEXT_PLAIN)
+///     .install();
+///
+/// let asset = BufAsset::new("page.tpl", &b""[..]);
+/// assert_eq!(asset.mime(), Some(mime::TEXT_HTML));
+/// # Ok::<(), mime::FromStrError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MimeRegistry {
+    extensions: BTreeMap<String, Mime>,
+    keys: BTreeMap<String, Mime>,
+}
+
+impl MimeRegistry {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            extensions: BTreeMap::new(),
+            keys: BTreeMap::new(),
+        }
+    }
+
+    /// The registry used by assets.
+    #[inline]
+    pub fn global() -> &'static RwLock<MimeRegistry> {
+        &GLOBAL
+    }
+
+    /// Replaces the global registry with this one.
+    pub fn install(self) {
+        *GLOBAL.write().unwrap_or_else(PoisonError::into_inner) = self;
+    }
+
+    /// Maps the extension `ext` to `mime`.
+    ///
+    /// Extensions are matched case-insensitively, with or without a leading
+    /// dot.
+    #[inline]
+    pub fn extension(mut self, ext: &str, mime: Mime) -> Self {
+        self.insert_extension(ext, mime);
+        self
+    }
+
+    /// Maps the asset `key` to `mime`, regardless of its extension.
+    #[inline]
+    pub fn key<S: Into<String>>(mut self, key: S, mime: Mime) -> Self {
+        self.insert_key(key, mime);
+        self
+    }
+
+    pub fn insert_extension(&mut self, ext: &str, mime: Mime) -> Option<Mime> {
+        self.extensions.insert(normalize_extension(ext), mime)
+    }
+
+    #[inline]
+    pub fn insert_key<S: Into<String>>(
+        &mut self,
+        key: S,
+        mime: Mime,
+    ) -> Option<Mime> {
+        self.keys.insert(key.into(), mime)
+    }
+
+    pub fn remove_extension(&mut self, ext: &str) -> Option<Mime> {
+        self.extensions.remove(&normalize_extension(ext))
+    }
+
+    #[inline]
+    pub fn remove_key(&mut self, key: &str) -> Option<Mime> {
+        self.keys.remove(key)
+    }
+
+    /// Looks up the override for `key`, then for the extension of `path`.
+    pub fn get(&self, key: Option<&Path>, path: &Path) -> Option<Mime> {
+        let by_key = key
+            .and_then(Path::to_str)
+            .and_then(|key| self.keys.get(key));
+        let by_ext = || {
+            let ext = path.extension()?.to_str()?;
+            self.extensions.get(&normalize_extension(ext))
+        };
+        by_key.or_else(by_ext).cloned()
+    }
+
+    /// Like [`get`](Self::get), falling back to the type guessed from the
+    /// extension of `path`.
+    pub fn guess(&self, key: Option<&Path>, path: &Path) -> Option<Mime> {
+        self.get(key, path)
+            .or_else(|| mime_guess::MimeGuess::from_path(path).first())
+    }
+}
+
+fn normalize_extension(ext: &str) -> String {
+    ext.trim_start_matches('.').to_ascii_lowercase()
+}
+
+/// Guesses the mime type of an asset using the global registry.
+pub(crate) fn guess_mime(key: Option<&Path>, path: &Path) -> Option<Mime> {
+    GLOBAL
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .guess(key, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_overrides_take_precedence() {
+        let registry = MimeRegistry::new()
+            .extension(".TPL", mime::TEXT_HTML)
+            .key("LICENSE", mime::TEXT_PLAIN);
+
+        let get = |key: Option<&str>, path: &str| {
+            registry.get(key.map(Path::new), Path::new(path))
+        };
+        assert_eq!(get(None, "page.tpl"), Some(mime::TEXT_HTML));
+        assert_eq!(get(None, "page.Tpl"), Some(mime::TEXT_HTML));
+        assert_eq!(get(Some("LICENSE"), "page.tpl"), Some(mime::TEXT_PLAIN));
+        assert_eq!(get(None, "LICENSE"), None);
+        assert_eq!(
+            registry.guess(None, Path::new("app.css")),
+            Some(mime::TEXT_CSS)
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use alloc::{format, string::String, vec::Vec};
+use std::path::PathBuf;
+
+use crate::{source::path_key, Asset, AssetSet, Error};
+
+impl AssetSet<'static, 'static> {
+    /// Creates a set of [`FileAsset`](crate::FileAsset)s for every file in
+    /// `dir`, using the default [`Scan`] options.
+    #[inline]
+    pub fn from_dir<P: Into<PathBuf>>(dir: P) -> Result<Self, Error> {
+        Self::scan(dir).build()
+    }
+
+    /// Returns a [`Scan`] of `dir` that can be configured before building
+    /// the set.
+    #[inline]
+    pub fn scan<P: Into<PathBuf>>(dir: P) -> Scan {
+        Scan::new(dir)
+    }
+}
+
+/// Options for scanning a directory into an [`AssetSet`].
+///
+/// Keys are the forward-slash separated paths relative to the directory,
+/// optionally with a [`prefix`](Scan::prefix). By default, hidden files are
+/// skipped and `.gitignore` and `.ignore` files inside the directory are
+/// respected, even outside of a git repository. Ignore files in parent
+/// directories are not read, so scanning e.g. `$OUT_DIR` works as expected.
+#[derive(Debug, Clone)]
+pub struct Scan {
+    dir: PathBuf,
+    prefix: String,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    ignore_files: Vec<String>,
+    hidden: bool,
+    git_ignore: bool,
+}
+
+impl Scan {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: dir.into(),
+            prefix: String::new(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            ignore_files: Vec::new(),
+            hidden: false,
+            git_ignore: true,
+        }
+    }
+
+    /// Prepends `prefix` to every key, e.g. `"static/"`.
+    #[inline]
+    pub fn prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Only includes files whose key matches `glob`.
+    ///
+    /// If called multiple times, files matching any of the globs are
+    /// included. Globs are matched against the key without the prefix.
+    #[inline]
+    pub fn include<S: Into<String>>(mut self, glob: S) -> Self {
+        self.include.push(glob.into());
+        self
+    }
+
+    /// Excludes files whose key matches `glob`, even if they are included.
+    #[inline]
+    pub fn exclude<S: Into<String>>(mut self, glob: S) -> Self {
+        self.exclude.push(glob.into());
+        self
+    }
+
+    /// Also respects ignore files with the given name, using the
+    /// `.gitignore` syntax.
+    #[inline]
+    pub fn ignore_file<S: Into<String>>(mut self, name: S) -> Self {
+        self.ignore_files.push(name.into());
+        self
+    }
+
+    /// Whether to include hidden files. Defaults to `false`.
+    #[inline]
+    pub fn hidden(mut self, yes: bool) -> Self {
+        self.hidden = yes;
+        self
+    }
+
+    /// Whether to respect `.gitignore` and `.ignore` files. Defaults to
+    /// `true`.
+    #[inline]
+    pub fn git_ignore(mut self, yes: bool) -> Self {
+        self.git_ignore = yes;
+        self
+    }
+
+    /// Scans the directory.
+    pub fn build(self) -> Result<AssetSet<'static, 'static>, Error> {
+        let include = glob_set(&self.include)?;
+        let exclude = glob_set(&self.exclude)?;
+
+        let mut walk = ignore::WalkBuilder::new(&self.dir);
+        walk.hidden(!self.hidden)
+            .parents(false)
+            .ignore(self.git_ignore)
+            .git_ignore(self.git_ignore)
+            .git_global(false)
+            .git_exclude(false)
+            .require_git(false)
+            .follow_links(true);
+        for name in &self.ignore_files {
+            walk.add_custom_ignore_filename(name);
+        }
+
+        let mut set = AssetSet::new();
+        for res in walk.build() {
+            let entry = res.map_err(Error::io)?;
+            if !entry.file_type().is_some_and(|ty| ty.is_file()) {
+                continue;
+            }
+
+            let rel = entry
+                .path()
+                .strip_prefix(&self.dir)
+                .expect("walked path should be inside the root");
+            let key = path_key(rel)?;
+            if (!self.include.is_empty() && !include.is_match(&key))
+                || exclude.is_match(&key)
+            {
+                continue;
+            }
+
+            let key = format!("{}{}", self.prefix, key);
+            set.insert(Asset::new_file(key, entry.into_path()));
+        }
+        Ok(set)
+    }
+}
+
+fn glob_set(globs: &[String]) -> Result<globset::GlobSet, Error> {
+    let mut builder = globset::GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(globset::Glob::new(glob).map_err(Error::parse)?);
+    }
+    builder.build().map_err(Error::parse)
+}
@@ -0,0 +1,265 @@
+//! Helpers for the `serde` feature.
+//!
+//! Binary contents are encoded as base64 strings in human-readable formats
+//! such as JSON, and as raw bytes in binary formats such as CBOR.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt;
+
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{Algorithm, Digest};
+
+/// (De)serializes contents as base64 or raw bytes.
+pub(crate) mod bytes {
+    use super::*;
+
+    pub(crate) fn serialize<S, C>(
+        contents: &C,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        C: AsRef<[u8]> + ?Sized,
+    {
+        let contents = contents.as_ref();
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&STANDARD.encode(contents))
+        } else {
+            serializer.serialize_bytes(contents)
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D, C>(deserializer: D) -> Result<C, D::Error>
+    where
+        D: Deserializer<'de>,
+        C: From<Vec<u8>>,
+    {
+        if deserializer.is_human_readable() {
+            let encoded = String::deserialize(deserializer)?;
+            STANDARD
+                .decode(encoded)
+                .map(C::from)
+                .map_err(de::Error::custom)
+        } else {
+            deserializer.deserialize_byte_buf(BytesVisitor).map(C::from)
+        }
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a byte array")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(
+        self,
+        v: Vec<u8>,
+    ) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+/// (De)serializes an optional mime type as a string.
+#[cfg(feature = "mime")]
+pub(crate) mod mime_opt {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        mime: &Option<mime::Mime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        mime.as_ref().map(mime::Mime::as_ref).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<mime::Mime>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|mime| mime.parse().map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+/// Digests are serialized as `algorithm:hex` strings, or as an
+/// `(algorithm, bytes)` tuple in binary formats.
+impl Serialize for Digest {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let digest = format!("{}:{}", self.algorithm(), self.to_hex());
+            serializer.serialize_str(&digest)
+        } else {
+            let algorithm = self.algorithm().as_str();
+            (algorithm, RawBytes(self.as_bytes())).serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Digest {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        let (algorithm, bytes) = if deserializer.is_human_readable() {
+            let digest = String::deserialize(deserializer)?;
+            let invalid = || {
+                de::Error::invalid_value(
+                    de::Unexpected::Str(&digest),
+                    &"an `algorithm:hex` digest",
+                )
+            };
+            let (algorithm, hex) =
+                digest.split_once(':').ok_or_else(invalid)?;
+            let bytes = decode_hex(hex).ok_or_else(invalid)?;
+            (String::from(algorithm), bytes)
+        } else {
+            let (algorithm, ByteBuf(bytes)) =
+                <(String, ByteBuf)>::deserialize(deserializer)?;
+            (algorithm, bytes)
+        };
+        let algorithm: Algorithm =
+            algorithm.parse().map_err(de::Error::custom)?;
+        Digest::from_bytes(algorithm, &bytes).ok_or_else(|| {
+            de::Error::invalid_length(bytes.len(), &"the digest length")
+        })
+    }
+}
+
+struct RawBytes<'a>(&'a [u8]);
+
+impl Serialize for RawBytes<'_> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Self, D::Error> {
+        deserializer.deserialize_byte_buf(BytesVisitor).map(ByteBuf)
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{borrow::Cow, vec};
+
+    use crate::{Asset, BufAsset, ContentEncoding, ProcessedAsset};
+
+    use super::*;
+
+    const HELLO_SHA256: &str =
+        "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    #[test]
+    fn buf_asset_contents_are_base64() {
+        let asset = BufAsset::new(String::from("a.txt"), &b"hello"[..]);
+        let json = serde_json::to_string(&asset).unwrap();
+        assert_eq!(json, r#"{"key":"a.txt","contents":"aGVsbG8="}"#);
+
+        let asset: BufAsset<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(asset.key, "a.txt");
+        assert_eq!(asset.contents, Cow::Borrowed(b"hello"));
+    }
+
+    #[test]
+    fn asset_variants_are_lowercase() {
+        let asset: Asset<String> =
+            Asset::new_buf(String::from("a.txt"), &b"hi"[..]);
+        let json = serde_json::to_string(&asset).unwrap();
+        assert_eq!(json, r#"{"buf":{"key":"a.txt","contents":"aGk="}}"#);
+
+        let json = r#"{"file":{"key":"a.txt","path":"assets/a.txt"}}"#;
+        let asset: Asset<String> = serde_json::from_str(json).unwrap();
+        assert!(asset.is_file());
+        assert_eq!(serde_json::to_string(&asset).unwrap(), json);
+    }
+
+    #[test]
+    fn digest_is_algorithm_and_hex() {
+        let digest = Digest::sha256(b"hello");
+        let json = serde_json::to_string(&digest).unwrap();
+        assert_eq!(json, format!("\"sha256:{HELLO_SHA256}\""));
+        assert_eq!(serde_json::from_str::<Digest>(&json).unwrap(), digest);
+    }
+
+    #[test]
+    fn invalid_digests_are_rejected() {
+        for json in [
+            format!("\"{HELLO_SHA256}\""),
+            format!("\"md5:{HELLO_SHA256}\""),
+            format!("\"sha256:{}\"", &HELLO_SHA256[1..]),
+            format!("\"sha256:{}\"", &HELLO_SHA256[2..]),
+            String::from("\"sha256:zz\""),
+        ] {
+            assert!(serde_json::from_str::<Digest>(&json).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn processed_asset_round_trips() {
+        let asset = ProcessedAsset {
+            key: String::from("app.css"),
+            contents: vec![1, 2, 3],
+            #[cfg(feature = "mime")]
+            mime: Some(mime::TEXT_CSS),
+            content_encoding: Some(ContentEncoding::Brotli),
+            digest: Digest::sha256(&[1, 2, 3]),
+        };
+        let json = serde_json::to_value(&asset).unwrap();
+        assert_eq!(json["contents"], "AQID");
+        assert_eq!(json["content_encoding"], "br");
+        #[cfg(feature = "mime")]
+        assert_eq!(json["mime"], "text/css");
+
+        let back: ProcessedAsset<String, Vec<u8>> =
+            serde_json::from_value(json).unwrap();
+        assert_eq!(back.key, asset.key);
+        assert_eq!(back.contents, asset.contents);
+        #[cfg(feature = "mime")]
+        assert_eq!(back.mime, asset.mime);
+        assert_eq!(back.content_encoding, asset.content_encoding);
+        assert_eq!(back.digest, asset.digest);
+    }
+}
@@ -0,0 +1,157 @@
+use alloc::{
+    collections::{btree_map, BTreeMap},
+    string::String,
+};
+
+use crate::Asset;
+
+/// A collection of [`Asset`]s ordered by key.
+///
+/// ```ignore
+/// let assets = AssetSet::scan("assets")
+///     .include("**/*.{css,js}")
+///     .exclude("vendor/**")
+///     .build()?
+///     .filter_mime(|mime| mime.type_() == mime::TEXT);
+///
+/// for (key, asset) in &assets {
+///     println!("{key}: {:?}", asset.mime());
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct AssetSet<'p, 'c> {
+    assets: BTreeMap<String, Asset<'p, 'c, String>>,
+}
+
+impl<'p, 'c> AssetSet<'p, 'c> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts an asset under its key, returning the asset it replaced.
+    pub fn insert(
+        &mut self,
+        asset: Asset<'p, 'c, String>,
+    ) -> Option<Asset<'p, 'c, String>> {
+        self.assets.insert(asset.key().clone(), asset)
+    }
+
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&Asset<'p, 'c, String>> {
+        self.assets.get(key)
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Asset<'p, 'c, String>> {
+        self.assets.get_mut(key)
+    }
+
+    #[inline]
+    pub fn remove(&mut self, key: &str) -> Option<Asset<'p, 'c, String>> {
+        self.assets.remove(key)
+    }
+
+    #[inline]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.assets.contains_key(key)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.assets.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+
+    /// Returns an iterator over the keys in order.
+    #[inline]
+    pub fn keys(&self) -> btree_map::Keys<'_, String, Asset<'p, 'c, String>> {
+        self.assets.keys()
+    }
+
+    /// Returns an iterator over the keys and assets in key order.
+    #[inline]
+    pub fn iter(&self) -> btree_map::Iter<'_, String, Asset<'p, 'c, String>> {
+        self.assets.iter()
+    }
+
+    /// Keeps only the assets for which `f` returns `true`.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&str, &Asset<'p, 'c, String>) -> bool,
+    {
+        self.assets.retain(|key, asset| f(key, asset));
+    }
+
+    /// Keeps only the assets with a known mime type for which `f` returns
+    /// `true`.
+    #[cfg(feature = "mime")]
+    pub fn filter_mime<F>(mut self, mut f: F) -> Self
+    where
+        F: FnMut(&mime::Mime) -> bool,
+    {
+        use crate::AssetExt as _;
+
+        self.assets
+            .retain(|_, asset| asset.mime().is_some_and(|mime| f(&mime)));
+        self
+    }
+
+    /// Moves every asset of `other` into this set.
+    ///
+    /// Assets in `other` replace assets with the same key in this set.
+    pub fn merge(&mut self, other: AssetSet<'p, 'c>) {
+        self.assets.extend(other.assets);
+    }
+}
+
+impl<'p, 'c> IntoIterator for AssetSet<'p, 'c> {
+    type IntoIter = btree_map::IntoValues<String, Asset<'p, 'c, String>>;
+    type Item = Asset<'p, 'c, String>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.assets.into_values()
+    }
+}
+
+impl<'a, 'p, 'c> IntoIterator for &'a AssetSet<'p, 'c> {
+    type IntoIter = btree_map::Iter<'a, String, Asset<'p, 'c, String>>;
+    type Item = (&'a String, &'a Asset<'p, 'c, String>);
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.assets.iter()
+    }
+}
+
+impl<'p, 'c> FromIterator<Asset<'p, 'c, String>> for AssetSet<'p, 'c> {
+    fn from_iter<I: IntoIterator<Item = Asset<'p, 'c, String>>>(
+        iter: I,
+    ) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<'p, 'c> Extend<Asset<'p, 'c, String>> for AssetSet<'p, 'c> {
+    fn extend<I: IntoIterator<Item = Asset<'p, 'c, String>>>(
+        &mut self,
+        iter: I,
+    ) {
+        for asset in iter {
+            self.insert(asset);
+        }
+    }
+}
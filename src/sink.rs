@@ -0,0 +1,108 @@
+use alloc::boxed::Box;
+
+use crate::{AssetSource, Error, Process};
+
+/// A place assets can be written to, such as a directory or an archive.
+///
+/// ```no_run
+/// use avenue::{AssetSink, BufAsset, DirSink, MemorySource};
+///
+/// let mut source = MemorySource::new();
+/// source.insert(BufAsset::new("style.css".into(), &b"body{}"[..]));
+///
+/// let mut sink = DirSink::new("dist");
+/// sink.write_source(&source)?;
+/// sink.write("robots.txt", b"User-agent: *")?;
+/// # Ok::<(), avenue::Error>(())
+/// ```
+pub trait AssetSink {
+    /// Writes `contents` under `key`.
+    fn write(&mut self, key: &str, contents: &[u8]) -> Result<(), Error>;
+
+    /// Processes `asset` and writes its output under `key`.
+    fn write_asset<T: Process>(
+        &mut self,
+        key: &str,
+        asset: T,
+    ) -> Result<(), Error>
+    where
+        Self: Sized,
+    {
+        let out = asset
+            .process_full()
+            .map_err(|err| Error::other(err).with_key(key))?;
+        self.write(key, out.as_ref())
+    }
+
+    /// Writes every asset of `source` under its key.
+    fn write_source<S: AssetSource + ?Sized>(
+        &mut self,
+        source: &S,
+    ) -> Result<(), Error>
+    where
+        Self: Sized,
+    {
+        for key in source.keys()? {
+            if let Some(asset) = source.open(&key)? {
+                self.write_asset(&key, asset)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: AssetSink + ?Sized> AssetSink for &mut S {
+    #[inline]
+    fn write(&mut self, key: &str, contents: &[u8]) -> Result<(), Error> {
+        S::write(self, key, contents)
+    }
+}
+
+impl<S: AssetSink + ?Sized> AssetSink for Box<S> {
+    #[inline]
+    fn write(&mut self, key: &str, contents: &[u8]) -> Result<(), Error> {
+        S::write(self, key, contents)
+    }
+}
+
+/// An [`AssetSink`] writing files into a directory.
+///
+/// Keys are used as relative paths, and parent directories are created as
+/// needed. Keys that would escape the directory or refer to hidden files are
+/// rejected.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct DirSink {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(feature = "std")]
+impl DirSink {
+    #[inline]
+    pub fn new<P: Into<std::path::PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    #[inline]
+    pub fn dir(&self) -> &std::path::Path {
+        &self.dir
+    }
+}
+
+#[cfg(feature = "std")]
+impl AssetSink for DirSink {
+    fn write(&mut self, key: &str, contents: &[u8]) -> Result<(), Error> {
+        if !crate::source::is_safe_key(key) {
+            return Err(Error::other("invalid key").with_key(key));
+        }
+
+        let path = self.dir.join(key);
+        let write = || {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, contents)
+        };
+        write().map_err(|err| Error::io(err).with_key(key))
+    }
+}
@@ -0,0 +1,173 @@
+//! Mime type detection from the contents of an asset.
+
+/// The number of leading bytes [`sniff`] looks at.
+pub const SNIFF_LEN: usize = 1024;
+
+/// Detects the mime type of `bytes` from their magic bytes.
+///
+/// Recognizes common binary formats (PNG, JPEG, GIF, WebP, WOFF, WOFF2, wasm,
+/// gzip, zip and PDF), and uses heuristics for HTML, SVG and XML. Only the
+/// first [`SNIFF_LEN`] bytes are inspected. Returns `None` for anything else,
+/// including plain text.
+pub fn sniff(bytes: &[u8]) -> Option<&'static str> {
+    const MAGIC: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"wOF2", "font/woff2"),
+        (b"wOFF", "font/woff"),
+        (b"\0asm", "application/wasm"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"%PDF-", "application/pdf"),
+    ];
+
+    let bytes = &bytes[..bytes.len().min(SNIFF_LEN)];
+    if let Some((_, mime)) =
+        MAGIC.iter().find(|(magic, _)| bytes.starts_with(magic))
+    {
+        return Some(mime);
+    }
+    if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    sniff_markup(bytes)
+}
+
+/// Detects HTML, SVG and XML documents from their leading tags.
+///
+/// Leading comments are skipped, since SVG files often start with one, e.g.
+/// from the tool that generated them.
+fn sniff_markup(bytes: &[u8]) -> Option<&'static str> {
+    let mut bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    loop {
+        let start = bytes.iter().position(|b| !b.is_ascii_whitespace())?;
+        bytes = &bytes[start..];
+        let Some(comment) = bytes.strip_prefix(b"<!--") else {
+            break;
+        };
+        let end = comment.windows(3).position(|w| w == b"-->")?;
+        bytes = &comment[end + 3..];
+    }
+
+    const HTML: &[&[u8]] =
+        &[b"<!doctype html", b"<html", b"<head", b"<body", b"<script"];
+    if HTML.iter().any(|tag| starts_with_ignore_case(bytes, tag)) {
+        return Some("text/html");
+    }
+    if starts_with_ignore_case(bytes, b"<svg") {
+        return Some("image/svg+xml");
+    }
+    if bytes.starts_with(b"<?xml") {
+        let is_svg = bytes.windows(4).any(|w| w.eq_ignore_ascii_case(b"<svg"));
+        return Some(if is_svg { "image/svg+xml" } else { "text/xml" });
+    }
+    None
+}
+
+fn starts_with_ignore_case(bytes: &[u8], prefix: &[u8]) -> bool {
+    bytes.len() >= prefix.len()
+        && bytes[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+/// Like [`sniff`], but returns a parsed [`Mime`](mime::Mime).
+#[cfg(feature = "mime")]
+pub fn sniff_mime(bytes: &[u8]) -> Option<mime::Mime> {
+    sniff(bytes).and_then(|mime| mime.parse().ok())
+}
+
+/// Returns the sniffed mime type of `bytes`, falling back to `guessed`.
+///
+/// This is useful when the extension of a key is not trusted, e.g. for user
+/// uploads. By default, assets only sniff their contents when no type can be
+/// guessed from the extension.
+///
+/// ```
+/// use avenue::{mime_prefer_sniffed, AssetExt, BufAsset};
+///
+/// let asset = BufAsset::new("upload.txt", &b"\x89PNG\r\n\x1a\n"[..]);
+/// let mime = mime_prefer_sniffed(asset.mime(), &asset.contents);
+///
+/// assert_eq!(mime, Some(mime::IMAGE_PNG));
+/// ```
+#[cfg(feature = "mime")]
+pub fn mime_prefer_sniffed(
+    guessed: Option<mime::Mime>,
+    bytes: &[u8],
+) -> Option<mime::Mime> {
+    sniff_mime(bytes).or(guessed)
+}
+
+/// Sniffs the mime type of the file at `path` from its first bytes.
+#[cfg(feature = "mime")]
+pub(crate) fn sniff_file(path: &std::path::Path) -> Option<mime::Mime> {
+    use std::io::Read as _;
+
+    let mut buf = alloc::vec::Vec::with_capacity(SNIFF_LEN);
+    std::fs::File::open(path)
+        .ok()?
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut buf)
+        .ok()?;
+    sniff_mime(&buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn magic_bytes() {
+        let cases: &[(&[u8], &str)] = &[
+            (b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", "image/png"),
+            (b"\xff\xd8\xff\xe0\0\x10JFIF", "image/jpeg"),
+            (b"GIF87a\x01\0", "image/gif"),
+            (b"GIF89a\x01\0", "image/gif"),
+            (b"wOF2\0\x01\0\0", "font/woff2"),
+            (b"wOFF\0\x01\0\0", "font/woff"),
+            (b"\0asm\x01\0\0\0", "application/wasm"),
+            (b"\x1f\x8b\x08\0", "application/gzip"),
+            (b"PK\x03\x04\x14\0", "application/zip"),
+            (b"%PDF-1.7\n", "application/pdf"),
+            (b"RIFF\x24\0\0\0WEBPVP8 ", "image/webp"),
+        ];
+        for (bytes, mime) in cases {
+            assert_eq!(sniff(bytes), Some(*mime), "{bytes:?}");
+        }
+
+        assert_eq!(sniff(b"RIFF\x24\0\0\0WAVEfmt "), None);
+        assert_eq!(sniff(b"RIFF"), None);
+        assert_eq!(sniff(b"\x89PN"), None);
+        assert_eq!(sniff(b""), None);
+        assert_eq!(sniff(b"plain text"), None);
+    }
+
+    #[test]
+    fn markup() {
+        assert_eq!(sniff(b"<!DOCTYPE html><html>"), Some("text/html"));
+        assert_eq!(sniff(b"<html lang=en>"), Some("text/html"));
+        assert_eq!(sniff(b"\n  \t<HEAD>"), Some("text/html"));
+        assert_eq!(sniff(b"\xef\xbb\xbf<!doctype html>"), Some("text/html"));
+        assert_eq!(sniff(b"<svg xmlns=\"\"/>"), Some("image/svg+xml"));
+        assert_eq!(sniff(b"\xef\xbb\xbf <svg/>"), Some("image/svg+xml"));
+        assert_eq!(
+            sniff(b"<?xml version=\"1.0\"?>\n<svg/>"),
+            Some("image/svg+xml")
+        );
+        assert_eq!(sniff(b"<?xml version=\"1.0\"?><feed/>"), Some("text/xml"));
+        assert_eq!(sniff(b"<div>"), None);
+    }
+
+    #[test]
+    fn skips_leading_comments() {
+        assert_eq!(sniff(b"<!-- c --><svg/>"), Some("image/svg+xml"));
+        assert_eq!(
+            sniff(b"<!-- Generator: Editor -->\n<!-- a -- b -->\n<svg/>"),
+            Some("image/svg+xml")
+        );
+        assert_eq!(sniff(b"<!-- c -->\n<!doctype html>"), Some("text/html"));
+        assert_eq!(sniff(b"<!-- unterminated <svg/>"), None);
+        assert_eq!(sniff(b"<!-- c -->"), None);
+    }
+}
@@ -0,0 +1,260 @@
+use alloc::{
+    boxed::Box, collections::BTreeMap, string::String, sync::Arc, vec::Vec,
+};
+
+#[cfg(feature = "std")]
+use crate::AssetSet;
+use crate::{Asset, BufAsset, Error};
+
+/// A place assets can be listed and opened from.
+///
+/// Sources can be layered with [`Overlay`], e.g. to serve per-tenant
+/// overrides that fall back to a shared base set.
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// use avenue::{AssetSource, BufAsset, MemorySource, Overlay};
+///
+/// let mut base = MemorySource::new();
+/// base.insert(BufAsset::new("logo.svg".into(), &b"<svg/>"[..]));
+/// base.insert(BufAsset::new("style.css".into(), &b"body{}"[..]));
+/// let base = Arc::new(base);
+///
+/// let mut acme = MemorySource::new();
+/// acme.insert(BufAsset::new("style.css".into(), &b"body{color:red}"[..]));
+/// let tenant = Overlay::new(acme, base.clone());
+///
+/// assert_eq!(tenant.keys()?, ["logo.svg", "style.css"]);
+/// let asset = tenant.open("style.css")?.unwrap();
+/// # Ok::<(), avenue::Error>(())
+/// ```
+pub trait AssetSource {
+    /// Returns the keys of every asset in this source, in order.
+    fn keys(&self) -> Result<Vec<String>, Error>;
+
+    /// Opens the asset with the given key, if it exists.
+    fn open(&self, key: &str) -> Result<Option<Asset<'_, '_, String>>, Error>;
+}
+
+impl<S: AssetSource + ?Sized> AssetSource for &S {
+    #[inline]
+    fn keys(&self) -> Result<Vec<String>, Error> {
+        S::keys(self)
+    }
+
+    #[inline]
+    fn open(&self, key: &str) -> Result<Option<Asset<'_, '_, String>>, Error> {
+        S::open(self, key)
+    }
+}
+
+impl<S: AssetSource + ?Sized> AssetSource for Box<S> {
+    #[inline]
+    fn keys(&self) -> Result<Vec<String>, Error> {
+        S::keys(self)
+    }
+
+    #[inline]
+    fn open(&self, key: &str) -> Result<Option<Asset<'_, '_, String>>, Error> {
+        S::open(self, key)
+    }
+}
+
+impl<S: AssetSource + ?Sized> AssetSource for Arc<S> {
+    #[inline]
+    fn keys(&self) -> Result<Vec<String>, Error> {
+        S::keys(self)
+    }
+
+    #[inline]
+    fn open(&self, key: &str) -> Result<Option<Asset<'_, '_, String>>, Error> {
+        S::open(self, key)
+    }
+}
+
+#[cfg(feature = "std")]
+impl AssetSource for AssetSet<'_, '_> {
+    fn keys(&self) -> Result<Vec<String>, Error> {
+        Ok(AssetSet::keys(self).cloned().collect())
+    }
+
+    fn open(&self, key: &str) -> Result<Option<Asset<'_, '_, String>>, Error> {
+        let asset = match self.get(key) {
+            Some(Asset::Buf(asset)) => {
+                Asset::new_buf(asset.key.clone(), &*asset.contents)
+            }
+            #[cfg(feature = "std")]
+            Some(Asset::File(asset)) => {
+                Asset::new_file(asset.key.clone(), &*asset.path)
+            }
+            None => return Ok(None),
+        };
+        Ok(Some(asset))
+    }
+}
+
+/// An [`AssetSource`] over [`BufAsset`]s held in memory.
+#[derive(Debug, Clone, Default)]
+pub struct MemorySource<'c> {
+    assets: BTreeMap<String, BufAsset<'c, String>>,
+}
+
+impl<'c> MemorySource<'c> {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts an asset under its key, returning the asset it replaced.
+    #[inline]
+    pub fn insert(
+        &mut self,
+        asset: BufAsset<'c, String>,
+    ) -> Option<BufAsset<'c, String>> {
+        self.assets.insert(asset.key.clone(), asset)
+    }
+
+    #[inline]
+    pub fn remove(&mut self, key: &str) -> Option<BufAsset<'c, String>> {
+        self.assets.remove(key)
+    }
+}
+
+impl AssetSource for MemorySource<'_> {
+    fn keys(&self) -> Result<Vec<String>, Error> {
+        Ok(self.assets.keys().cloned().collect())
+    }
+
+    fn open(&self, key: &str) -> Result<Option<Asset<'_, '_, String>>, Error> {
+        Ok(self
+            .assets
+            .get(key)
+            .map(|asset| Asset::new_buf(asset.key.clone(), &*asset.contents)))
+    }
+}
+
+impl<'c> FromIterator<BufAsset<'c, String>> for MemorySource<'c> {
+    fn from_iter<I: IntoIterator<Item = BufAsset<'c, String>>>(
+        iter: I,
+    ) -> Self {
+        Self {
+            assets: iter
+                .into_iter()
+                .map(|asset| (asset.key.clone(), asset))
+                .collect(),
+        }
+    }
+}
+
+/// An [`AssetSource`] over the files in a directory.
+///
+/// Keys are listed like [`AssetSet::from_dir`], so hidden and ignored files
+/// are skipped. Opening a key never escapes the directory: keys with `..`,
+/// absolute or hidden components are treated as missing.
+#[cfg(feature = "fs")]
+#[derive(Debug, Clone)]
+pub struct DirSource {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(feature = "fs")]
+impl DirSource {
+    #[inline]
+    pub fn new<P: Into<std::path::PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    #[inline]
+    pub fn dir(&self) -> &std::path::Path {
+        &self.dir
+    }
+}
+
+#[cfg(feature = "fs")]
+impl AssetSource for DirSource {
+    fn keys(&self) -> Result<Vec<String>, Error> {
+        let set = AssetSet::from_dir(&self.dir)?;
+        Ok(AssetSet::keys(&set).cloned().collect())
+    }
+
+    fn open(&self, key: &str) -> Result<Option<Asset<'_, '_, String>>, Error> {
+        if !is_safe_key(key) {
+            return Ok(None);
+        }
+
+        let path = self.dir.join(key);
+        match std::fs::metadata(&path) {
+            Ok(meta) if meta.is_file() => {
+                Ok(Some(Asset::new_file(String::from(key), path)))
+            }
+            Ok(_) => Ok(None),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(Error::io(err).with_key(key)),
+        }
+    }
+}
+
+/// An [`AssetSource`] that layers one source over another.
+///
+/// Assets in `top` take precedence over assets with the same key in `base`.
+/// Overlays can be nested to stack more than two layers.
+#[derive(Debug, Clone)]
+pub struct Overlay<T, B> {
+    pub top: T,
+    pub base: B,
+}
+
+impl<T, B> Overlay<T, B> {
+    #[inline]
+    pub fn new(top: T, base: B) -> Self {
+        Self { top, base }
+    }
+}
+
+impl<T: AssetSource, B: AssetSource> AssetSource for Overlay<T, B> {
+    fn keys(&self) -> Result<Vec<String>, Error> {
+        let mut keys = self.top.keys()?;
+        keys.extend(self.base.keys()?);
+        keys.sort_unstable();
+        keys.dedup();
+        Ok(keys)
+    }
+
+    fn open(&self, key: &str) -> Result<Option<Asset<'_, '_, String>>, Error> {
+        match self.top.open(key)? {
+            Some(asset) => Ok(Some(asset)),
+            None => self.base.open(key),
+        }
+    }
+}
+
+/// Returns whether `key` can be joined to a directory without escaping it or
+/// referring to a hidden file.
+#[cfg(feature = "std")]
+pub(crate) fn is_safe_key(key: &str) -> bool {
+    key.split('/').all(|segment| {
+        !segment.is_empty()
+            && !segment.starts_with('.')
+            && !segment.contains(['\\', ':'])
+    })
+}
+
+/// Joins the components of a relative path with forward slashes.
+#[cfg(any(feature = "fs", feature = "tar"))]
+pub(crate) fn path_key(rel: &std::path::Path) -> Result<String, Error> {
+    let mut key = String::new();
+    for component in rel.components() {
+        let component = component.as_os_str().to_str().ok_or_else(|| {
+            Error::other(alloc::format!(
+                "`{}` is not valid UTF-8",
+                rel.display()
+            ))
+        })?;
+        if !key.is_empty() {
+            key.push('/');
+        }
+        key.push_str(component);
+    }
+    Ok(key)
+}
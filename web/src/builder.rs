@@ -1,6 +1,25 @@
-use avenue::{AssetExt, Process};
+#[cfg(any(
+    feature = "minify-js",
+    feature = "lightningcss",
+    feature = "minify-html"
+))]
+use avenue::AssetExt;
+use avenue::Process;
+#[cfg(any(
+    feature = "minify-js",
+    feature = "lightningcss",
+    feature = "minify-html"
+))]
 use either::Either;
 
+#[cfg(any(
+    feature = "brotli",
+    feature = "flate2",
+    feature = "zstd",
+    feature = "minify-js",
+    feature = "lightningcss",
+    feature = "minify-html"
+))]
 use crate::*;
 
 /// This trait defines certain operations you can do on an asset, but not how
@@ -89,6 +108,10 @@ pub trait Builder {
     }
 
     /// Attempts to minify this asset based on its mime type.
+    ///
+    /// Assets whose mime type is unknown are passed through unchanged. Custom
+    /// extensions, such as templates, can be mapped to a minifiable type
+    /// using [`MimeRegistry`](avenue::MimeRegistry).
     #[cfg(any(
         feature = "minify-js",
         feature = "lightningcss",
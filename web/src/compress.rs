@@ -1,25 +1,112 @@
+#[cfg(feature = "tokio")]
+use std::future::Future;
+#[cfg(feature = "zstd")]
+use std::io::BufReader;
+#[cfg(feature = "flate2")]
 use std::io::Read;
 
-use avenue::{BoxError, Process};
+#[cfg(feature = "tokio")]
+use avenue::ProcessAsync;
+use avenue::{
+    AssetExt, ContentEncoding, Error, FromIoRead, IntoProcessRead, IoRead,
+    Process,
+};
+
+/// The internal buffer size used by streaming encoders.
+#[cfg(feature = "brotli")]
+const BUFFER_SIZE: usize = 8 * 1024;
 
 #[cfg(feature = "brotli")]
 pub struct CompressBrotli<T>(pub T);
 
 #[cfg(feature = "brotli")]
 impl<T: Process> Process for CompressBrotli<T> {
-    type Error = BoxError;
+    type Error = Error;
     type Output = Vec<u8>;
 
     fn process_full(self) -> Result<Self::Output, Self::Error> {
-        let out = self.0.process_full().map_err(Into::into)?;
-        let mut src = out.as_ref();
-        let mut buf = Vec::with_capacity(src.len());
-        brotli::BrotliCompress(
-            &mut src,
-            &mut buf,
+        let out = self.0.process_full().map_err(Error::other)?;
+        encode_brotli(out.as_ref())
+    }
+}
+
+#[cfg(feature = "brotli")]
+impl<T: IntoProcessRead> IntoProcessRead for CompressBrotli<T> {
+    type Error = Error;
+    type Reader = FromIoRead<brotli::CompressorReader<IoRead<T::Reader>>>;
+
+    fn into_process_read(self) -> Result<Self::Reader, Self::Error> {
+        let src = self.0.into_process_read().map_err(Error::other)?;
+        let enc = brotli::CompressorReader::with_params(
+            IoRead(src),
+            BUFFER_SIZE,
             &brotli::enc::BrotliEncoderParams::default(),
-        )?;
-        Ok(buf)
+        );
+        Ok(FromIoRead(enc))
+    }
+}
+
+#[cfg(all(feature = "brotli", feature = "tokio"))]
+impl<T> ProcessAsync for CompressBrotli<T>
+where
+    T: ProcessAsync,
+    T::Output: Send,
+{
+    type Error = Error;
+    type Output = Vec<u8>;
+
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        let fut = self.0.process_async();
+        async move {
+            let out = fut.await.map_err(Error::other)?;
+            let src = out.as_ref().to_vec();
+            tokio::task::spawn_blocking(move || encode_brotli(&src))
+                .await
+                .map_err(Error::other)?
+        }
+    }
+}
+
+#[cfg(feature = "brotli")]
+fn encode_brotli(mut src: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::with_capacity(src.len());
+    brotli::BrotliCompress(
+        &mut src,
+        &mut buf,
+        &brotli::enc::BrotliEncoderParams::default(),
+    )
+    .map_err(|err| Error::encode(err).with_stage("compress_brotli"))?;
+    Ok(buf)
+}
+
+#[cfg(feature = "brotli")]
+impl<T: AssetExt> AssetExt for CompressBrotli<T> {
+    #[inline]
+    fn mime(&self) -> Option<mime::Mime> {
+        self.0.mime()
+    }
+
+    #[inline]
+    fn path(&self) -> Option<&std::path::Path> {
+        self.0.path()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        // The compressed size is not known until the asset is processed.
+        None
+    }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        Some(ContentEncoding::Brotli)
+    }
+
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        self.0.last_modified()
     }
 }
 
@@ -28,17 +115,86 @@ pub struct CompressDeflate<T>(pub T);
 
 #[cfg(feature = "flate2")]
 impl<T: Process> Process for CompressDeflate<T> {
-    type Error = BoxError;
+    type Error = Error;
     type Output = Vec<u8>;
 
     fn process_full(self) -> Result<Self::Output, Self::Error> {
-        let out = self.0.process_full().map_err(Into::into)?;
-        let src = out.as_ref();
-        let mut buf = Vec::with_capacity(src.len());
-        let mut enc =
-            flate2::bufread::DeflateEncoder::new(src, Default::default());
-        enc.read_to_end(&mut buf)?;
-        Ok(buf)
+        let out = self.0.process_full().map_err(Error::other)?;
+        encode_deflate(out.as_ref())
+    }
+}
+
+#[cfg(feature = "flate2")]
+impl<T: IntoProcessRead> IntoProcessRead for CompressDeflate<T> {
+    type Error = Error;
+    type Reader = FromIoRead<flate2::read::DeflateEncoder<IoRead<T::Reader>>>;
+
+    fn into_process_read(self) -> Result<Self::Reader, Self::Error> {
+        let src = self.0.into_process_read().map_err(Error::other)?;
+        let enc =
+            flate2::read::DeflateEncoder::new(IoRead(src), Default::default());
+        Ok(FromIoRead(enc))
+    }
+}
+
+#[cfg(all(feature = "flate2", feature = "tokio"))]
+impl<T> ProcessAsync for CompressDeflate<T>
+where
+    T: ProcessAsync,
+    T::Output: Send,
+{
+    type Error = Error;
+    type Output = Vec<u8>;
+
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        let fut = self.0.process_async();
+        async move {
+            let out = fut.await.map_err(Error::other)?;
+            let src = out.as_ref().to_vec();
+            tokio::task::spawn_blocking(move || encode_deflate(&src))
+                .await
+                .map_err(Error::other)?
+        }
+    }
+}
+
+#[cfg(feature = "flate2")]
+fn encode_deflate(src: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::with_capacity(src.len());
+    let mut enc = flate2::bufread::DeflateEncoder::new(src, Default::default());
+    enc.read_to_end(&mut buf)
+        .map_err(|err| Error::encode(err).with_stage("compress_deflate"))?;
+    Ok(buf)
+}
+
+#[cfg(feature = "flate2")]
+impl<T: AssetExt> AssetExt for CompressDeflate<T> {
+    #[inline]
+    fn mime(&self) -> Option<mime::Mime> {
+        self.0.mime()
+    }
+
+    #[inline]
+    fn path(&self) -> Option<&std::path::Path> {
+        self.0.path()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        // The compressed size is not known until the asset is processed.
+        None
+    }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        Some(ContentEncoding::Deflate)
+    }
+
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        self.0.last_modified()
     }
 }
 
@@ -47,16 +203,85 @@ pub struct CompressGzip<T>(pub T);
 
 #[cfg(feature = "flate2")]
 impl<T: Process> Process for CompressGzip<T> {
-    type Error = BoxError;
+    type Error = Error;
     type Output = Vec<u8>;
 
     fn process_full(self) -> Result<Vec<u8>, Self::Error> {
-        let out = self.0.process_full().map_err(Into::into)?;
-        let src = out.as_ref();
-        let mut buf = Vec::with_capacity(src.len());
-        let mut enc = flate2::bufread::GzEncoder::new(src, Default::default());
-        enc.read_to_end(&mut buf)?;
-        Ok(buf)
+        let out = self.0.process_full().map_err(Error::other)?;
+        encode_gzip(out.as_ref())
+    }
+}
+
+#[cfg(feature = "flate2")]
+impl<T: IntoProcessRead> IntoProcessRead for CompressGzip<T> {
+    type Error = Error;
+    type Reader = FromIoRead<flate2::read::GzEncoder<IoRead<T::Reader>>>;
+
+    fn into_process_read(self) -> Result<Self::Reader, Self::Error> {
+        let src = self.0.into_process_read().map_err(Error::other)?;
+        let enc = flate2::read::GzEncoder::new(IoRead(src), Default::default());
+        Ok(FromIoRead(enc))
+    }
+}
+
+#[cfg(all(feature = "flate2", feature = "tokio"))]
+impl<T> ProcessAsync for CompressGzip<T>
+where
+    T: ProcessAsync,
+    T::Output: Send,
+{
+    type Error = Error;
+    type Output = Vec<u8>;
+
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        let fut = self.0.process_async();
+        async move {
+            let out = fut.await.map_err(Error::other)?;
+            let src = out.as_ref().to_vec();
+            tokio::task::spawn_blocking(move || encode_gzip(&src))
+                .await
+                .map_err(Error::other)?
+        }
+    }
+}
+
+#[cfg(feature = "flate2")]
+fn encode_gzip(src: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::with_capacity(src.len());
+    let mut enc = flate2::bufread::GzEncoder::new(src, Default::default());
+    enc.read_to_end(&mut buf)
+        .map_err(|err| Error::encode(err).with_stage("compress_gzip"))?;
+    Ok(buf)
+}
+
+#[cfg(feature = "flate2")]
+impl<T: AssetExt> AssetExt for CompressGzip<T> {
+    #[inline]
+    fn mime(&self) -> Option<mime::Mime> {
+        self.0.mime()
+    }
+
+    #[inline]
+    fn path(&self) -> Option<&std::path::Path> {
+        self.0.path()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        // The compressed size is not known until the asset is processed.
+        None
+    }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        Some(ContentEncoding::Gzip)
+    }
+
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        self.0.last_modified()
     }
 }
 
@@ -65,13 +290,84 @@ pub struct CompressZstd<T>(pub T);
 
 #[cfg(feature = "zstd")]
 impl<T: Process> Process for CompressZstd<T> {
-    type Error = BoxError;
+    type Error = Error;
     type Output = Vec<u8>;
 
     fn process_full(self) -> Result<Vec<u8>, Self::Error> {
-        let out = self.0.process_full().map_err(Into::into)?;
-        let src = out.as_ref();
-        let buf = zstd::encode_all(src, 0)?;
-        Ok(buf)
+        let out = self.0.process_full().map_err(Error::other)?;
+        encode_zstd(out.as_ref())
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl<T: IntoProcessRead> IntoProcessRead for CompressZstd<T> {
+    type Error = Error;
+    type Reader = FromIoRead<
+        zstd::stream::read::Encoder<'static, BufReader<IoRead<T::Reader>>>,
+    >;
+
+    fn into_process_read(self) -> Result<Self::Reader, Self::Error> {
+        let src = self.0.into_process_read().map_err(Error::other)?;
+        let enc = zstd::stream::read::Encoder::new(IoRead(src), 0)
+            .map_err(|err| Error::encode(err).with_stage("compress_zstd"))?;
+        Ok(FromIoRead(enc))
+    }
+}
+
+#[cfg(all(feature = "zstd", feature = "tokio"))]
+impl<T> ProcessAsync for CompressZstd<T>
+where
+    T: ProcessAsync,
+    T::Output: Send,
+{
+    type Error = Error;
+    type Output = Vec<u8>;
+
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        let fut = self.0.process_async();
+        async move {
+            let out = fut.await.map_err(Error::other)?;
+            let src = out.as_ref().to_vec();
+            tokio::task::spawn_blocking(move || encode_zstd(&src))
+                .await
+                .map_err(Error::other)?
+        }
+    }
+}
+
+#[cfg(feature = "zstd")]
+fn encode_zstd(src: &[u8]) -> Result<Vec<u8>, Error> {
+    zstd::encode_all(src, 0)
+        .map_err(|err| Error::encode(err).with_stage("compress_zstd"))
+}
+
+#[cfg(feature = "zstd")]
+impl<T: AssetExt> AssetExt for CompressZstd<T> {
+    #[inline]
+    fn mime(&self) -> Option<mime::Mime> {
+        self.0.mime()
+    }
+
+    #[inline]
+    fn path(&self) -> Option<&std::path::Path> {
+        self.0.path()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        // The compressed size is not known until the asset is processed.
+        None
+    }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        Some(ContentEncoding::Zstd)
+    }
+
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        self.0.last_modified()
     }
 }
@@ -1,7 +1,25 @@
 mod builder;
+#[cfg(any(feature = "brotli", feature = "flate2", feature = "zstd"))]
 mod compress;
+#[cfg(any(
+    feature = "minify-js",
+    feature = "lightningcss",
+    feature = "minify-html"
+))]
 mod minify;
+#[cfg(feature = "lightningcss")]
+mod rewrite;
+mod stage;
 
 pub use builder::Builder;
+#[cfg(any(feature = "brotli", feature = "flate2", feature = "zstd"))]
 pub use compress::*;
+#[cfg(any(
+    feature = "minify-js",
+    feature = "lightningcss",
+    feature = "minify-html"
+))]
 pub use minify::*;
+#[cfg(feature = "lightningcss")]
+pub use rewrite::{Rewrite, Rewritten};
+pub use stage::WebStage;
@@ -1,28 +1,64 @@
-use avenue::{AssetExt, BoxError, Process};
+#[cfg(feature = "tokio")]
+use std::future::Future;
+
+#[cfg(feature = "lightningcss")]
+use avenue::BoxError;
+#[cfg(feature = "tokio")]
+use avenue::ProcessAsync;
+use avenue::{AssetExt, ContentEncoding, Error, Process};
 
 #[cfg(feature = "minify-js")]
 pub struct MinifyJs<T>(pub T);
 
 #[cfg(feature = "minify-js")]
 impl<T: Process> Process for MinifyJs<T> {
-    type Error = BoxError;
+    type Error = Error;
     type Output = Vec<u8>;
 
     fn process_full(self) -> Result<Self::Output, Self::Error> {
-        let out = self.0.process_full().map_err(Into::into)?;
-        let src = out.as_ref();
-        let mut buf = Vec::with_capacity(src.len());
-        minify_js::minify(
-            &minify_js::Session::new(),
-            minify_js::TopLevelMode::Global,
-            src,
-            &mut buf,
-        )
-        .map_err(|err| format!("failed to minify js: {:?}", err))?;
-        Ok(buf)
+        let out = self.0.process_full().map_err(Error::other)?;
+        js(out.as_ref())
+    }
+}
+
+#[cfg(all(feature = "minify-js", feature = "tokio"))]
+impl<T> ProcessAsync for MinifyJs<T>
+where
+    T: ProcessAsync,
+    T::Output: Send,
+{
+    type Error = Error;
+    type Output = Vec<u8>;
+
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        let fut = self.0.process_async();
+        async move {
+            let out = fut.await.map_err(Error::other)?;
+            let src = out.as_ref().to_vec();
+            tokio::task::spawn_blocking(move || js(&src))
+                .await
+                .map_err(Error::other)?
+        }
     }
 }
 
+#[cfg(feature = "minify-js")]
+pub(crate) fn js(src: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut buf = Vec::with_capacity(src.len());
+    minify_js::minify(
+        &minify_js::Session::new(),
+        minify_js::TopLevelMode::Global,
+        src,
+        &mut buf,
+    )
+    .map_err(|err| {
+        Error::parse(format!("invalid js: {:?}", err)).with_stage("minify_js")
+    })?;
+    Ok(buf)
+}
+
 #[cfg(feature = "minify-js")]
 impl<T: AssetExt> AssetExt for MinifyJs<T> {
     #[inline]
@@ -39,6 +75,16 @@ impl<T: AssetExt> AssetExt for MinifyJs<T> {
     fn size_hint(&self) -> Option<usize> {
         self.0.size_hint()
     }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        self.0.content_encoding()
+    }
+
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        self.0.last_modified()
+    }
 }
 
 #[cfg(feature = "lightningcss")]
@@ -46,28 +92,61 @@ pub struct MinifyCss<T>(pub T);
 
 #[cfg(feature = "lightningcss")]
 impl<T: Process> Process for MinifyCss<T> {
-    type Error = BoxError;
+    type Error = Error;
     type Output = Vec<u8>;
 
     fn process_full(self) -> Result<Self::Output, Self::Error> {
-        use lightningcss::{
-            printer::PrinterOptions,
-            stylesheet::{MinifyOptions, ParserOptions, StyleSheet},
-        };
-
-        let out = self.0.process_full().map_err(Into::into)?;
-        let src = out.as_ref();
-        let src_utf8 = std::str::from_utf8(src)?;
-        let mut stylesheet =
-            StyleSheet::parse(src_utf8, ParserOptions::default())
-                .map_err(|err| format!("failed to parse css: {:?}", err))?;
-        stylesheet.minify(MinifyOptions::default())?;
-        let css = stylesheet.to_css(PrinterOptions {
+        let out = self.0.process_full().map_err(Error::other)?;
+        css(out.as_ref())
+    }
+}
+
+#[cfg(all(feature = "lightningcss", feature = "tokio"))]
+impl<T> ProcessAsync for MinifyCss<T>
+where
+    T: ProcessAsync,
+    T::Output: Send,
+{
+    type Error = Error;
+    type Output = Vec<u8>;
+
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        let fut = self.0.process_async();
+        async move {
+            let out = fut.await.map_err(Error::other)?;
+            let src = out.as_ref().to_vec();
+            tokio::task::spawn_blocking(move || css(&src))
+                .await
+                .map_err(Error::other)?
+        }
+    }
+}
+
+#[cfg(feature = "lightningcss")]
+pub(crate) fn css(src: &[u8]) -> Result<Vec<u8>, Error> {
+    use lightningcss::{
+        printer::PrinterOptions,
+        stylesheet::{MinifyOptions, ParserOptions, StyleSheet},
+    };
+
+    let parse_err = |err: BoxError| Error::parse(err).with_stage("minify_css");
+
+    let src_utf8 =
+        std::str::from_utf8(src).map_err(|err| parse_err(err.into()))?;
+    let mut stylesheet = StyleSheet::parse(src_utf8, ParserOptions::default())
+        .map_err(|err| parse_err(format!("invalid css: {:?}", err).into()))?;
+    stylesheet
+        .minify(MinifyOptions::default())
+        .map_err(|err| parse_err(err.into()))?;
+    let css = stylesheet
+        .to_css(PrinterOptions {
             minify: true,
             ..Default::default()
-        })?;
-        Ok(css.code.into())
-    }
+        })
+        .map_err(|err| Error::encode(err).with_stage("minify_css"))?;
+    Ok(css.code.into())
 }
 
 #[cfg(feature = "lightningcss")]
@@ -86,6 +165,16 @@ impl<T: AssetExt> AssetExt for MinifyCss<T> {
     fn size_hint(&self) -> Option<usize> {
         self.0.size_hint()
     }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        self.0.content_encoding()
+    }
+
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        self.0.last_modified()
+    }
 }
 
 #[cfg(feature = "minify-html")]
@@ -93,17 +182,43 @@ pub struct MinifyHtml<T>(pub T);
 
 #[cfg(feature = "minify-html")]
 impl<T: Process> Process for MinifyHtml<T> {
-    type Error = BoxError;
+    type Error = Error;
     type Output = Vec<u8>;
 
     fn process_full(self) -> Result<Self::Output, Self::Error> {
-        let out = self.0.process_full().map_err(Into::into)?;
-        let src = out.as_ref();
-        let buf = minify_html::minify(src, &minify_html::Cfg::spec_compliant());
-        Ok(buf)
+        let out = self.0.process_full().map_err(Error::other)?;
+        Ok(html(out.as_ref()))
+    }
+}
+
+#[cfg(all(feature = "minify-html", feature = "tokio"))]
+impl<T> ProcessAsync for MinifyHtml<T>
+where
+    T: ProcessAsync,
+    T::Output: Send,
+{
+    type Error = Error;
+    type Output = Vec<u8>;
+
+    fn process_async(
+        self,
+    ) -> impl Future<Output = Result<Self::Output, Self::Error>> + Send {
+        let fut = self.0.process_async();
+        async move {
+            let out = fut.await.map_err(Error::other)?;
+            let src = out.as_ref().to_vec();
+            tokio::task::spawn_blocking(move || Ok(html(&src)))
+                .await
+                .map_err(Error::other)?
+        }
     }
 }
 
+#[cfg(feature = "minify-html")]
+pub(crate) fn html(src: &[u8]) -> Vec<u8> {
+    minify_html::minify(src, &minify_html::Cfg::spec_compliant())
+}
+
 #[cfg(feature = "minify-html")]
 impl<T: AssetExt> AssetExt for MinifyHtml<T> {
     #[inline]
@@ -120,6 +235,16 @@ impl<T: AssetExt> AssetExt for MinifyHtml<T> {
     fn size_hint(&self) -> Option<usize> {
         self.0.size_hint()
     }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        self.0.content_encoding()
+    }
+
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        self.0.last_modified()
+    }
 }
 
 #[cfg(any(
@@ -142,7 +267,7 @@ pub enum Minify<T> {
     feature = "minify-html"
 ))]
 impl<T: Process> Process for Minify<T> {
-    type Error = BoxError;
+    type Error = Error;
     type Output = Vec<u8>;
 
     fn process_full(self) -> Result<Self::Output, Self::Error> {
@@ -198,4 +323,28 @@ impl<T: AssetExt> AssetExt for Minify<T> {
             Minify::Html(minify_html) => minify_html.size_hint(),
         }
     }
+
+    #[inline]
+    fn content_encoding(&self) -> Option<ContentEncoding> {
+        match self {
+            #[cfg(feature = "minify-js")]
+            Minify::Js(minify_js) => minify_js.content_encoding(),
+            #[cfg(feature = "lightningcss")]
+            Minify::Css(minify_css) => minify_css.content_encoding(),
+            #[cfg(feature = "minify-html")]
+            Minify::Html(minify_html) => minify_html.content_encoding(),
+        }
+    }
+
+    #[inline]
+    fn last_modified(&self) -> Option<std::time::SystemTime> {
+        match self {
+            #[cfg(feature = "minify-js")]
+            Minify::Js(minify_js) => minify_js.last_modified(),
+            #[cfg(feature = "lightningcss")]
+            Minify::Css(minify_css) => minify_css.last_modified(),
+            #[cfg(feature = "minify-html")]
+            Minify::Html(minify_html) => minify_html.last_modified(),
+        }
+    }
 }
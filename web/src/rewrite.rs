@@ -0,0 +1,1500 @@
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    ops::Range,
+};
+
+use avenue::{Asset, AssetExt, AssetSet, Error, Fingerprint, Process};
+use lightningcss::{
+    dependencies::{Dependency, DependencyOptions},
+    printer::PrinterOptions,
+    stylesheet::{ParserOptions, StyleAttribute, StyleSheet, ToCssResult},
+};
+
+/// Fingerprints every asset of a set, rewriting the references between them
+/// to the new keys.
+///
+/// References are found in CSS (`url()` and `@import`), HTML (`src`, `href`,
+/// `srcset`, `poster` and `imagesrcset` attributes, `<style>` elements,
+/// `style` attributes and inline scripts) and JavaScript (`import` and
+/// `export ... from` specifiers and `import()` calls). Relative,
+/// root-relative and, for JavaScript, `./` and `../` specifiers are resolved
+/// against the key of the referencing asset. Absolute URLs, bare specifiers
+/// and references to keys outside of the set are left untouched.
+/// Stylesheets, and inline styles with references, are printed again by
+/// lightningcss, while HTML and JavaScript are only modified where
+/// references are replaced. Invalid stylesheets and inline styles are
+/// reported as errors, rather than leaving their references stale.
+///
+/// HTML and JavaScript are scanned by small tokenizers rather than parsers:
+/// minify-html does not expose its parser, and the syntax tree of parse-js,
+/// used by minify-js, has no source locations for module specifiers, so
+/// neither can be used to replace references in place. The tokenizers skip
+/// comments, strings, template literals, regular expressions and raw text
+/// elements, but do not handle:
+///
+/// - scripts other than JavaScript, such as import maps and JSON;
+/// - character references in attributes, other than in `style` attributes;
+/// - attributes not listed above, such as `<object data>`, `<meta content>`
+///   and `<form action>`;
+/// - `import()` calls with anything but a single string literal, and other
+///   ways to load modules, such as `new URL(..., import.meta.url)`,
+///   `new Worker()` and `require()`;
+/// - regular expressions following a `)`, such as `if (a) /b/.test(c)`,
+///   which are read as divisions.
+///
+/// Since the fingerprint of an asset depends on the rewritten references to
+/// its dependencies, assets are processed in dependency order. Assets that
+/// reference each other in a cycle are fingerprinted as a group: each of them
+/// gets a hash of the contents of the whole group, before the references
+/// within the group are rewritten.
+///
+/// ```ignore
+/// let rewritten = Rewrite::new()
+///     .fingerprint(Fingerprint::new().length(10))
+///     .run(AssetSet::from_dir("assets")?)?;
+///
+/// assert_eq!(rewritten.keys["css/app.css"], "css/app.3f9a1c2b04.css");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rewrite {
+    fingerprint: Fingerprint,
+    fingerprint_html: bool,
+}
+
+/// The output of a [`Rewrite`].
+#[derive(Debug, Clone, Default)]
+pub struct Rewritten {
+    /// The rewritten assets under their new keys.
+    pub assets: AssetSet<'static, 'static>,
+    /// The new key of every asset, by original key.
+    pub keys: BTreeMap<String, String>,
+}
+
+impl Rewrite {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            fingerprint: Fingerprint::new(),
+            fingerprint_html: false,
+        }
+    }
+
+    #[inline]
+    pub fn fingerprint(mut self, fingerprint: Fingerprint) -> Self {
+        self.fingerprint = fingerprint;
+        self
+    }
+
+    /// Whether HTML documents are fingerprinted too.
+    ///
+    /// Defaults to `false`, as documents are usually requested by their
+    /// original URL. References inside of them are rewritten either way.
+    #[inline]
+    pub fn fingerprint_html(mut self, yes: bool) -> Self {
+        self.fingerprint_html = yes;
+        self
+    }
+
+    pub fn run(&self, assets: AssetSet<'_, '_>) -> Result<Rewritten, Error> {
+        let keys: BTreeSet<String> = assets.keys().cloned().collect();
+        let mut entries = BTreeMap::new();
+        for asset in assets {
+            let key = asset.key().clone();
+            let entry = self
+                .load(&keys, asset)
+                .map_err(|err| err.with_key(&key).with_stage("rewrite"))?;
+            entries.insert(key, entry);
+        }
+
+        let mut out = Rewritten::default();
+        for (key, entry) in &entries {
+            if !entry.fingerprinted {
+                out.keys.insert(key.clone(), key.clone());
+            }
+        }
+        for group in components(&entries) {
+            // References within the group are left as they are, since the
+            // new keys of the group are not known yet.
+            let mut contents: Vec<Vec<u8>> = group
+                .iter()
+                .map(|key| {
+                    entries[*key].render(key, &self.fingerprint, &out.keys)
+                })
+                .collect();
+            let cyclic =
+                group.len() > 1 || entries[group[0]].refers_to(group[0]);
+
+            let input = match &contents[..] {
+                [contents] => Cow::Borrowed(&contents[..]),
+                _ => Cow::Owned(group_input(&group, &contents)),
+            };
+            for key in &group {
+                if entries[*key].fingerprinted {
+                    let new_key = self.fingerprint.key(key, &input);
+                    out.keys.insert((*key).to_owned(), new_key);
+                }
+            }
+            if cyclic {
+                contents = group
+                    .iter()
+                    .map(|key| {
+                        entries[*key].render(key, &self.fingerprint, &out.keys)
+                    })
+                    .collect();
+            }
+
+            for (key, contents) in group.iter().zip(contents) {
+                let new_key = out.keys[*key].clone();
+                out.assets.insert(Asset::new_buf(new_key, contents));
+            }
+        }
+        Ok(out)
+    }
+
+    fn load(
+        &self,
+        keys: &BTreeSet<String>,
+        asset: Asset<'_, '_, String>,
+    ) -> Result<Entry, Error> {
+        let lang = asset.mime().as_ref().and_then(Lang::from_mime);
+        let fingerprinted = self.fingerprint_html || lang != Some(Lang::Html);
+        let key = asset.key().clone();
+        let contents = asset.process_full()?.into_owned();
+
+        let (code, refs) = match lang {
+            Some(Lang::Css) => css_refs(&contents)?,
+            Some(Lang::Html) => html_refs(&contents)?,
+            Some(Lang::Js) => {
+                let refs = js_refs(&contents);
+                (contents, refs)
+            }
+            None => (contents, Vec::new()),
+        };
+        let refs = refs
+            .into_iter()
+            .map(|r| {
+                let target = resolve(&key, &r.url, r.lang)
+                    .filter(|(target, _)| keys.contains(target));
+                Ref { target, ..r }
+            })
+            .collect();
+
+        Ok(Entry {
+            code,
+            refs,
+            fingerprinted,
+        })
+    }
+}
+
+#[cfg(feature = "manifest")]
+impl Rewritten {
+    /// Returns a manifest mapping the original keys to the new ones.
+    ///
+    /// The rewritten assets are not encoded, so `encodings` returns the
+    /// content encodings each of them is served in, e.g. the precompressed
+    /// variants written alongside it.
+    pub fn manifest<F>(
+        &self,
+        mut encodings: F,
+    ) -> Result<avenue::Manifest, Error>
+    where
+        F: FnMut(&avenue::ManifestEntry) -> Vec<avenue::ContentEncoding>,
+    {
+        let mut manifest = avenue::Manifest::new();
+        for (src, file) in &self.keys {
+            let Some(asset) = self.assets.get(file) else {
+                continue;
+            };
+            let mime = asset.mime();
+            let contents = asset.clone().process_full()?;
+            let mut entry = avenue::ManifestEntry::new(src, file, &contents);
+            entry.mime = mime;
+            entry.encodings = encodings(&entry);
+            manifest.insert(entry);
+        }
+        Ok(manifest)
+    }
+}
+
+impl Default for Rewrite {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Groups the keys into the strongly connected components of the references
+/// to fingerprinted assets, with the dependencies of each group before it.
+///
+/// This is Tarjan's algorithm, which emits the components in reverse
+/// topological order.
+fn components(entries: &BTreeMap<String, Entry>) -> Vec<Vec<&str>> {
+    struct State<'a> {
+        entries: &'a BTreeMap<String, Entry>,
+        index: BTreeMap<&'a str, usize>,
+        stack: Vec<&'a str>,
+        on_stack: BTreeSet<&'a str>,
+        components: Vec<Vec<&'a str>>,
+    }
+
+    /// Visits `key`, returning the lowest index reachable from it.
+    fn visit<'a>(state: &mut State<'a>, key: &'a str) -> usize {
+        let index = state.index.len();
+        state.index.insert(key, index);
+        state.stack.push(key);
+        state.on_stack.insert(key);
+
+        let entries = state.entries;
+        let mut low = index;
+        for (dep, _) in
+            entries[key].refs.iter().filter_map(|r| r.target.as_ref())
+        {
+            if !entries[dep].fingerprinted {
+                continue;
+            }
+            match state.index.get(dep.as_str()) {
+                None => low = low.min(visit(state, dep)),
+                Some(&i) if state.on_stack.contains(dep.as_str()) => {
+                    low = low.min(i)
+                }
+                Some(_) => {}
+            }
+        }
+
+        if low == index {
+            let mut component = Vec::new();
+            while let Some(member) = state.stack.pop() {
+                state.on_stack.remove(member);
+                component.push(member);
+                if member == key {
+                    break;
+                }
+            }
+            component.sort_unstable();
+            state.components.push(component);
+        }
+        low
+    }
+
+    let mut state = State {
+        entries,
+        index: BTreeMap::new(),
+        stack: Vec::new(),
+        on_stack: BTreeSet::new(),
+        components: Vec::new(),
+    };
+    for key in entries.keys() {
+        if !state.index.contains_key(key.as_str()) {
+            visit(&mut state, key);
+        }
+    }
+    state.components
+}
+
+/// The bytes hashed for each key of a group of assets referencing each other.
+fn group_input(keys: &[&str], contents: &[Vec<u8>]) -> Vec<u8> {
+    let mut input = Vec::new();
+    for (key, contents) in keys.iter().zip(contents) {
+        input.extend_from_slice(key.as_bytes());
+        input.push(0);
+        input.extend_from_slice(&(contents.len() as u64).to_le_bytes());
+        input.extend_from_slice(contents);
+    }
+    input
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lang {
+    Css,
+    Html,
+    Js,
+}
+
+impl Lang {
+    fn from_mime(mime: &mime::Mime) -> Option<Self> {
+        match mime.subtype() {
+            mime::CSS => Some(Lang::Css),
+            mime::HTML => Some(Lang::Html),
+            mime::JAVASCRIPT => Some(Lang::Js),
+            _ => None,
+        }
+    }
+
+    /// Escapes `url` to replace a reference in this language.
+    fn escape(self, url: &str) -> String {
+        match self {
+            Lang::Css => url.replace('\\', "\\\\").replace('"', "\\\""),
+            Lang::Html => url.replace('"', "&quot;").replace('\'', "&#39;"),
+            Lang::Js => url
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\'', "\\'"),
+        }
+    }
+}
+
+struct Entry {
+    code: Vec<u8>,
+    refs: Vec<Ref>,
+    fingerprinted: bool,
+}
+
+/// A reference to another asset, found at `range` of the code.
+struct Ref {
+    range: Range<usize>,
+    url: String,
+    /// The language the reference is written in, which may differ from the
+    /// language of the asset for inline styles and scripts.
+    lang: Lang,
+    /// Whether the reference is in a `style` attribute, and must be escaped
+    /// for HTML as well.
+    in_attr: bool,
+    /// The key of the referenced asset and the query and fragment of `url`.
+    target: Option<(String, String)>,
+}
+
+impl Entry {
+    fn refers_to(&self, key: &str) -> bool {
+        self.refs
+            .iter()
+            .any(|r| r.target.as_ref().is_some_and(|(target, _)| target == key))
+    }
+
+    /// Replaces the references to assets whose key changed.
+    ///
+    /// References to keys missing from `keys` are left as they are.
+    fn render(
+        &self,
+        key: &str,
+        fingerprint: &Fingerprint,
+        keys: &BTreeMap<String, String>,
+    ) -> Vec<u8> {
+        // The hash only changes the file name of a fingerprinted key, or
+        // adds a directory named after it with `Placement::Directory`. An
+        // empty asset can stand in for the final key, as long as that
+        // directory is not taken to be shared with the key of another asset.
+        let base = if self.fingerprinted {
+            fingerprint.key(key, &[])
+        } else {
+            key.to_owned()
+        };
+        let hashed_dir = parent(&base) != parent(key);
+
+        let mut out = Vec::with_capacity(self.code.len());
+        let mut pos = 0;
+        for r in &self.refs {
+            let changed = r.target.as_ref().and_then(|(target, suffix)| {
+                let new_key = keys.get(target).filter(|k| *k != target)?;
+                Some((new_key, suffix))
+            });
+            let url = match changed {
+                Some((new_key, suffix)) => {
+                    locate(&base, hashed_dir, &r.url, new_key, suffix)
+                }
+                // CSS references are replaced by placeholders, so the other
+                // ones must be written back.
+                None if self.code[r.range.clone()] != *r.url.as_bytes() => {
+                    r.url.clone()
+                }
+                None => continue,
+            };
+            out.extend_from_slice(&self.code[pos..r.range.start]);
+            let mut url = r.lang.escape(&url);
+            if r.in_attr {
+                url = Lang::Html.escape(&url);
+            }
+            out.extend_from_slice(url.as_bytes());
+            pos = r.range.end;
+        }
+        out.extend_from_slice(&self.code[pos..]);
+        out
+    }
+}
+
+/// Resolves `url` against the key of the asset it was found in, returning
+/// the referenced key and the query and fragment of `url`.
+fn resolve(base: &str, url: &str, lang: Lang) -> Option<(String, String)> {
+    let url = url.trim();
+    if url.is_empty() || url.starts_with('#') || url.starts_with("//") {
+        return None;
+    }
+    let scheme = url
+        .find(':')
+        .is_some_and(|i| !url[..i].contains(['/', '?', '#']));
+    if scheme {
+        return None;
+    }
+    if lang == Lang::Js
+        && !["./", "../", "/"].iter().any(|p| url.starts_with(p))
+    {
+        return None;
+    }
+
+    let (path, suffix) =
+        url.split_at(url.find(['?', '#']).unwrap_or(url.len()));
+    let mut segments: Vec<&str> = match path.strip_prefix('/') {
+        Some(_) => Vec::new(),
+        None => parent(base).split('/').filter(|s| !s.is_empty()).collect(),
+    };
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+    Some((segments.join("/"), suffix.to_owned()))
+}
+
+/// Builds the URL of `target` as found in the asset at `base`, keeping the
+/// style of the original `url`.
+///
+/// With `hashed_dir`, the last directory of `base` is a stand-in for a hash
+/// and is never shared with `target`.
+fn locate(
+    base: &str,
+    hashed_dir: bool,
+    url: &str,
+    target: &str,
+    suffix: &str,
+) -> String {
+    let url = url.trim();
+    if url.starts_with('/') {
+        return format!("/{target}{suffix}");
+    }
+
+    let from: Vec<&str> =
+        parent(base).split('/').filter(|s| !s.is_empty()).collect();
+    let to: Vec<&str> = target.split('/').collect();
+    let shared = from.len() - usize::from(hashed_dir);
+    let common = from
+        .iter()
+        .zip(&to)
+        .take_while(|(a, b)| a == b)
+        .count()
+        .min(to.len() - 1)
+        .min(shared);
+
+    let mut path = "../".repeat(from.len() - common);
+    if path.is_empty() && (url.starts_with("./") || url.starts_with("../")) {
+        path.push_str("./");
+    }
+    path.push_str(&to[common..].join("/"));
+    path.push_str(suffix);
+    path
+}
+
+fn parent(key: &str) -> &str {
+    key.rfind('/').map_or("", |i| &key[..i])
+}
+
+/// Prints the stylesheet with placeholders in place of references.
+fn css_refs(src: &[u8]) -> Result<(Vec<u8>, Vec<Ref>), Error> {
+    let src = std::str::from_utf8(src).map_err(Error::parse)?;
+    let stylesheet = StyleSheet::parse(src, ParserOptions::default())
+        .map_err(|err| Error::parse(format!("invalid css: {:?}", err)))?;
+    let css = stylesheet
+        .to_css(css_printer_options())
+        .map_err(Error::encode)?;
+    Ok(placeholder_refs(css))
+}
+
+/// Prints the declarations of a `style` attribute with placeholders in place
+/// of references.
+fn style_attr_refs(src: &str) -> Result<(Vec<u8>, Vec<Ref>), Error> {
+    let style = StyleAttribute::parse(src, ParserOptions::default()).map_err(
+        |err| Error::parse(format!("invalid style attribute: {:?}", err)),
+    )?;
+    let css = style.to_css(css_printer_options()).map_err(Error::encode)?;
+    Ok(placeholder_refs(css))
+}
+
+fn css_printer_options<'a>() -> PrinterOptions<'a> {
+    PrinterOptions {
+        analyze_dependencies: Some(DependencyOptions {
+            remove_imports: false,
+        }),
+        ..Default::default()
+    }
+}
+
+fn placeholder_refs(css: ToCssResult) -> (Vec<u8>, Vec<Ref>) {
+    let mut refs = Vec::new();
+    let mut placeholders = BTreeSet::new();
+    for dep in css.dependencies.into_iter().flatten() {
+        let (url, placeholder) = match dep {
+            Dependency::Import(dep) => (dep.url, dep.placeholder),
+            Dependency::Url(dep) => (dep.url, dep.placeholder),
+        };
+        if !placeholders.insert(placeholder.clone()) {
+            continue;
+        }
+        for (start, _) in css.code.match_indices(&placeholder) {
+            refs.push(Ref {
+                range: start..start + placeholder.len(),
+                url: url.clone(),
+                lang: Lang::Css,
+                in_attr: false,
+                target: None,
+            });
+        }
+    }
+    refs.sort_by_key(|r| r.range.start);
+    (css.code.into_bytes(), refs)
+}
+
+/// Finds the references in an HTML document.
+///
+/// This looks at the URL attributes of elements, `<style>` elements, `style`
+/// attributes and inline scripts, skipping comments and the contents of the
+/// other raw text elements. Inline styles with references are printed again
+/// by lightningcss, so the document is returned with the references.
+fn html_refs(src: &[u8]) -> Result<(Vec<u8>, Vec<Ref>), Error> {
+    const URL_ATTRS: &[&[u8]] =
+        &[b"href", b"src", b"poster", b"srcset", b"imagesrcset"];
+    const RAW_TEXT: &[&[u8]] = &[b"script", b"style", b"textarea", b"title"];
+
+    let mut out = Splice::new(src);
+    let mut i = 0;
+    while let Some(lt) = find(src, i, b"<") {
+        i = lt + 1;
+        if src[i..].starts_with(b"!--") {
+            i = find(src, i, b"-->").map_or(src.len(), |end| end + 3);
+            continue;
+        }
+
+        let name_start = i;
+        while i < src.len()
+            && (src[i].is_ascii_alphanumeric() || src[i] == b'-')
+        {
+            i += 1;
+        }
+        let name = &src[name_start..i];
+        if name.is_empty() {
+            // A closing tag, doctype or stray `<`.
+            continue;
+        }
+
+        let mut has_src = false;
+        let mut ty = None;
+        loop {
+            while i < src.len()
+                && (src[i].is_ascii_whitespace() || src[i] == b'/')
+            {
+                i += 1;
+            }
+            if i >= src.len() {
+                break;
+            }
+            if src[i] == b'>' {
+                i += 1;
+                break;
+            }
+
+            let attr_start = i;
+            while i < src.len()
+                && !src[i].is_ascii_whitespace()
+                && !matches!(src[i], b'=' | b'>' | b'/')
+            {
+                i += 1;
+            }
+            let attr = &src[attr_start..i];
+            if attr.is_empty() {
+                i += 1;
+                continue;
+            }
+            while i < src.len() && src[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if src.get(i) != Some(&b'=') {
+                continue;
+            }
+            i += 1;
+            while i < src.len() && src[i].is_ascii_whitespace() {
+                i += 1;
+            }
+
+            let value_start = i;
+            let value = match src.get(i) {
+                Some(&quote @ (b'"' | b'\'')) => {
+                    let end = find(src, i + 1, &[quote]).unwrap_or(src.len());
+                    let value = i + 1..end;
+                    i = (end + 1).min(src.len());
+                    value
+                }
+                _ => {
+                    let start = i;
+                    while i < src.len()
+                        && !src[i].is_ascii_whitespace()
+                        && src[i] != b'>'
+                    {
+                        i += 1;
+                    }
+                    start..i
+                }
+            };
+
+            if attr.eq_ignore_ascii_case(b"type") {
+                ty = Some(&src[value.clone()]);
+            } else if attr.eq_ignore_ascii_case(b"style") {
+                let css = std::str::from_utf8(&src[value.clone()])
+                    .map_err(Error::parse)?;
+                let (code, refs) = style_attr_refs(&decode_attr(css))?;
+                if !refs.is_empty() {
+                    let (code, refs) = encode_attr(&code, refs);
+                    out.replace(value_start..i, &code, refs);
+                }
+                continue;
+            }
+            if !URL_ATTRS.iter().any(|a| attr.eq_ignore_ascii_case(a)) {
+                continue;
+            }
+            has_src |= attr.eq_ignore_ascii_case(b"src");
+            let srcset = attr.len() >= 6
+                && attr[attr.len() - 6..].eq_ignore_ascii_case(b"srcset");
+            let ranges: Vec<_> = if srcset {
+                srcset_urls(src, value).collect()
+            } else {
+                vec![trim(src, value)]
+            };
+            for range in ranges.into_iter().filter(|r| !r.is_empty()) {
+                let url = String::from_utf8_lossy(&src[range.clone()]);
+                let r = Ref {
+                    range: 0..range.len(),
+                    url: url.into_owned(),
+                    lang: Lang::Html,
+                    in_attr: false,
+                    target: None,
+                };
+                out.replace(range.clone(), &src[range], vec![r]);
+            }
+        }
+
+        if let Some(raw) =
+            RAW_TEXT.iter().find(|t| name.eq_ignore_ascii_case(t))
+        {
+            let mut close = vec![b'<', b'/'];
+            close.extend_from_slice(raw);
+            let end = find_ignore_case(src, i, &close).unwrap_or(src.len());
+            let text = i..end;
+            let ty = ty.map_or(&b""[..], |ty| &ty[trim(ty, 0..ty.len())]);
+            if *raw == b"script" && !has_src && is_js_type(ty) {
+                let refs = js_refs(&src[text.clone()]);
+                if !refs.is_empty() {
+                    out.replace(text.clone(), &src[text], refs);
+                }
+            } else if *raw == b"style"
+                && (ty.is_empty() || ty.eq_ignore_ascii_case(b"text/css"))
+            {
+                let (code, refs) = css_refs(&src[text.clone()])?;
+                if !refs.is_empty() {
+                    out.replace(text, &code, refs);
+                }
+            }
+            i = end;
+        }
+    }
+    Ok(out.finish())
+}
+
+/// Copies a document while replacing parts of it, collecting the references
+/// in the copy.
+struct Splice<'s> {
+    src: &'s [u8],
+    pos: usize,
+    code: Vec<u8>,
+    refs: Vec<Ref>,
+}
+
+impl<'s> Splice<'s> {
+    fn new(src: &'s [u8]) -> Self {
+        Self {
+            src,
+            pos: 0,
+            code: Vec::with_capacity(src.len()),
+            refs: Vec::new(),
+        }
+    }
+
+    /// Replaces `range` of the source, which must not start before the end
+    /// of the previous one, with `code` containing `refs`.
+    fn replace(&mut self, range: Range<usize>, code: &[u8], refs: Vec<Ref>) {
+        self.code
+            .extend_from_slice(&self.src[self.pos..range.start]);
+        let offset = self.code.len();
+        self.code.extend_from_slice(code);
+        self.refs.extend(refs.into_iter().map(|r| Ref {
+            range: r.range.start + offset..r.range.end + offset,
+            ..r
+        }));
+        self.pos = range.end;
+    }
+
+    fn finish(mut self) -> (Vec<u8>, Vec<Ref>) {
+        self.code.extend_from_slice(&self.src[self.pos..]);
+        (self.code, self.refs)
+    }
+}
+
+/// Whether a `<script>` with the given `type` attribute is JavaScript.
+fn is_js_type(ty: &[u8]) -> bool {
+    let ends_with = |suffix: &[u8]| {
+        ty.len() >= suffix.len()
+            && ty[ty.len() - suffix.len()..].eq_ignore_ascii_case(suffix)
+    };
+    ty.is_empty()
+        || ty.eq_ignore_ascii_case(b"module")
+        || ends_with(b"/javascript")
+        || ends_with(b"/ecmascript")
+}
+
+/// Decodes the character references in an attribute value.
+///
+/// Numeric references and the named ones used to escape markup are decoded,
+/// other named references are left as they are.
+fn decode_attr(value: &str) -> Cow<'_, str> {
+    if !value.contains('&') {
+        return Cow::Borrowed(value);
+    }
+
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                name => {
+                    let num = name.strip_prefix('#')?;
+                    let code = match num.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => num.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    Cow::Owned(out)
+}
+
+/// Encodes `code` as a double-quoted attribute value, keeping track of the
+/// references in it.
+fn encode_attr(code: &[u8], refs: Vec<Ref>) -> (Vec<u8>, Vec<Ref>) {
+    let escape = |out: &mut Vec<u8>, code: &[u8]| {
+        for &b in code {
+            match b {
+                b'&' => out.extend_from_slice(b"&amp;"),
+                b'"' => out.extend_from_slice(b"&quot;"),
+                b => out.push(b),
+            }
+        }
+    };
+
+    let mut out = vec![b'"'];
+    let mut pos = 0;
+    let refs = refs
+        .into_iter()
+        .map(|r| {
+            escape(&mut out, &code[pos..r.range.start]);
+            let start = out.len();
+            out.extend_from_slice(&code[r.range.clone()]);
+            pos = r.range.end;
+            Ref {
+                range: start..out.len(),
+                in_attr: true,
+                ..r
+            }
+        })
+        .collect();
+    escape(&mut out, &code[pos..]);
+    out.push(b'"');
+    (out, refs)
+}
+
+/// Splits a `srcset` attribute value into the ranges of its URLs.
+fn srcset_urls(
+    src: &[u8],
+    value: Range<usize>,
+) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut i = value.start;
+    std::iter::from_fn(move || {
+        while i < value.end && (src[i].is_ascii_whitespace() || src[i] == b',')
+        {
+            i += 1;
+        }
+        if i >= value.end {
+            return None;
+        }
+        let start = i;
+        while i < value.end && !src[i].is_ascii_whitespace() && src[i] != b',' {
+            i += 1;
+        }
+        let url = start..i;
+        while i < value.end && src[i] != b',' {
+            i += 1;
+        }
+        Some(url)
+    })
+}
+
+fn trim(src: &[u8], mut range: Range<usize>) -> Range<usize> {
+    while range.start < range.end && src[range.start].is_ascii_whitespace() {
+        range.start += 1;
+    }
+    while range.start < range.end && src[range.end - 1].is_ascii_whitespace() {
+        range.end -= 1;
+    }
+    range
+}
+
+fn find(src: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    src.get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|i| from + i)
+}
+
+fn find_ignore_case(src: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    src.get(from..)?
+        .windows(needle.len())
+        .position(|w| w.eq_ignore_ascii_case(needle))
+        .map(|i| from + i)
+}
+
+/// Finds the module specifiers of a JavaScript module.
+///
+/// This tokenizes just enough of the language to skip comments, strings,
+/// template literals and regular expressions, and looks for the string
+/// literals following `import`, `from` in `import` and `export` statements,
+/// and `import(`.
+fn js_refs(src: &[u8]) -> Vec<Ref> {
+    let toks = js_tokens(src);
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < toks.len() {
+        let after_dot = i > 0 && toks[i - 1] == Tok::Punct(b'.');
+        let keyword = match toks[i] {
+            Tok::Ident(ident) if !after_dot => ident,
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        i += 1;
+        match (keyword, toks.get(i)) {
+            (b"import", Some(Tok::Str(range))) => ranges.push(range.clone()),
+            (b"import", Some(Tok::Punct(b'('))) => {
+                if let Some(Tok::Str(range)) = toks.get(i + 1) {
+                    ranges.push(range.clone());
+                }
+            }
+            (b"import", Some(Tok::Ident(_) | Tok::Punct(b'{' | b'*')))
+            | (b"export", Some(Tok::Punct(b'{' | b'*'))) => {
+                // Look for `from "..."` before the end of the statement.
+                while let Some(tok) = toks.get(i) {
+                    match tok {
+                        Tok::Ident(b"from") => {
+                            if let Some(Tok::Str(range)) = toks.get(i + 1) {
+                                ranges.push(range.clone());
+                                break;
+                            }
+                        }
+                        Tok::Punct(b';') | Tok::Str(_) => break,
+                        Tok::Ident(b"import" | b"export") => break,
+                        _ => {}
+                    }
+                    i += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    ranges
+        .into_iter()
+        .map(|range| Ref {
+            url: String::from_utf8_lossy(&src[range.clone()]).into_owned(),
+            range,
+            lang: Lang::Js,
+            in_attr: false,
+            target: None,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Tok<'s> {
+    Ident(&'s [u8]),
+    /// A string literal, with the range of its contents.
+    Str(Range<usize>),
+    Punct(u8),
+    /// A number, template literal or regular expression.
+    Other,
+}
+
+fn js_tokens(src: &[u8]) -> Vec<Tok<'_>> {
+    let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$';
+    let is_ident = move |b: u8| is_ident(b) || b >= 0x80;
+
+    let mut toks = Vec::new();
+    // Whether each open brace is a template literal substitution.
+    let mut braces = Vec::new();
+    let mut i = 0;
+    while i < src.len() {
+        let next = src.get(i + 1).copied();
+        let tok = match src[i] {
+            b if b.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b'/' if next == Some(b'/') => {
+                i = find(src, i, b"\n").unwrap_or(src.len());
+                continue;
+            }
+            b'/' if next == Some(b'*') => {
+                i = find(src, i + 2, b"*/").map_or(src.len(), |end| end + 2);
+                continue;
+            }
+            quote @ (b'"' | b'\'') => {
+                let start = i + 1;
+                i = start;
+                while i < src.len() && !matches!(src[i], b'\n') {
+                    match src[i] {
+                        b'\\' => i += 2,
+                        b if b == quote => break,
+                        _ => i += 1,
+                    }
+                }
+                let end = i.min(src.len());
+                i = end + 1;
+                Tok::Str(start..end)
+            }
+            b'`' => {
+                i = skip_template(src, i + 1, &mut braces);
+                Tok::Other
+            }
+            b'{' => {
+                braces.push(false);
+                i += 1;
+                Tok::Punct(b'{')
+            }
+            b'}' if braces.pop() == Some(true) => {
+                i = skip_template(src, i + 1, &mut braces);
+                Tok::Other
+            }
+            b'/' if regex_allowed(toks.last()) => {
+                let mut class = false;
+                i += 1;
+                while i < src.len() && src[i] != b'\n' {
+                    match src[i] {
+                        b'\\' => i += 1,
+                        b'[' => class = true,
+                        b']' => class = false,
+                        b'/' if !class => break,
+                        _ => {}
+                    }
+                    i += 1;
+                }
+                i += 1;
+                while i < src.len() && is_ident(src[i]) {
+                    i += 1;
+                }
+                Tok::Other
+            }
+            b if b.is_ascii_digit() => {
+                while i < src.len() && (is_ident(src[i]) || src[i] == b'.') {
+                    i += 1;
+                }
+                Tok::Other
+            }
+            b if is_ident(b) => {
+                let start = i;
+                while i < src.len() && is_ident(src[i]) {
+                    i += 1;
+                }
+                Tok::Ident(&src[start..i])
+            }
+            b => {
+                i += 1;
+                Tok::Punct(b)
+            }
+        };
+        toks.push(tok);
+    }
+    toks
+}
+
+/// Skips the rest of a template literal, or up to the next substitution.
+fn skip_template(src: &[u8], mut i: usize, braces: &mut Vec<bool>) -> usize {
+    while i < src.len() {
+        match src[i] {
+            b'\\' => i += 2,
+            b'`' => return i + 1,
+            b'$' if src.get(i + 1) == Some(&b'{') => {
+                braces.push(true);
+                return i + 2;
+            }
+            _ => i += 1,
+        }
+    }
+    src.len()
+}
+
+/// Whether a `/` after `prev` starts a regular expression, rather than
+/// being a division.
+fn regex_allowed(prev: Option<&Tok<'_>>) -> bool {
+    const KEYWORDS: &[&[u8]] = &[
+        b"return",
+        b"typeof",
+        b"instanceof",
+        b"in",
+        b"of",
+        b"new",
+        b"delete",
+        b"void",
+        b"throw",
+        b"case",
+        b"do",
+        b"else",
+        b"yield",
+        b"await",
+    ];
+
+    match prev {
+        None => true,
+        Some(Tok::Punct(b')' | b']' | b'}')) => false,
+        Some(Tok::Punct(_)) => true,
+        Some(Tok::Ident(ident)) => KEYWORDS.contains(ident),
+        Some(Tok::Str(_) | Tok::Other) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(assets: &[(&str, &str)]) -> Rewritten {
+        let mut set = AssetSet::new();
+        for (key, contents) in assets {
+            set.insert(Asset::new_buf(
+                String::from(*key),
+                contents.as_bytes().to_vec(),
+            ));
+        }
+        Rewrite::new().run(set).unwrap()
+    }
+
+    fn contents(out: &Rewritten, key: &str) -> String {
+        let asset = out.assets.get(&out.keys[key]).unwrap();
+        String::from_utf8(asset.clone().process_full().unwrap().into_owned())
+            .unwrap()
+    }
+
+    /// Returns the URLs of the references in an HTML document that is not
+    /// modified, checking that they match their ranges.
+    fn html_urls(src: &str) -> Vec<String> {
+        let (code, refs) = html_refs(src.as_bytes()).unwrap();
+        assert_eq!(code, src.as_bytes());
+        urls(src, refs)
+    }
+
+    /// Returns the URLs of `refs`, checking that they match their ranges.
+    fn urls(src: &str, refs: Vec<Ref>) -> Vec<String> {
+        refs.into_iter()
+            .map(|r| {
+                assert_eq!(src[r.range.clone()], r.url);
+                r.url
+            })
+            .collect()
+    }
+
+    #[test]
+    fn html_attributes() {
+        let src = r#"<!doctype html>
+<link rel=stylesheet href=app.css>
+<IMG SRC = " a.png " alt="<img src=no.png>">
+<img srcset="a-1x.png 1x, a-2x.png 2x,a-3x.png" src='a.png'>
+<link imagesrcset="hero.png 800w" data-src="no.png">
+<video poster="poster.jpg"></video>
+<a href="">empty</a>"#;
+        assert_eq!(
+            html_urls(src),
+            [
+                "app.css",
+                "a.png",
+                "a-1x.png",
+                "a-2x.png",
+                "a-3x.png",
+                "a.png",
+                "hero.png",
+                "poster.jpg"
+            ]
+        );
+    }
+
+    #[test]
+    fn html_skips_comments_and_raw_text() {
+        let src = r#"<!-- <img src="no.png"> -->
+<script src="app.js">import "./no.js"; let s = '<img src="no.png">';</script>
+<script type="importmap">{"imports": {"a": "./no.js"}}</script>
+<style>a::after { content: '<a href="no.html">' }</STYLE>
+<title><a href="no.html"></title>
+<textarea><a href="no.html"></textarea>
+<a href="yes.html">"#;
+        assert_eq!(html_urls(src), ["app.js", "yes.html"]);
+    }
+
+    #[test]
+    fn js_specifiers() {
+        let src = r#"import a from "./a.js";
+import { b,
+  c } from './b.js';
+import * as d from "./d.js";
+import "./side-effect.js";
+import {from} from "./from.js";
+export * from "./e.js";
+export { f } from "./f.js";
+const g = await import("./g.js");
+"#;
+        assert_eq!(
+            urls(src, js_refs(src.as_bytes())),
+            [
+                "./a.js",
+                "./b.js",
+                "./d.js",
+                "./side-effect.js",
+                "./from.js",
+                "./e.js",
+                "./f.js",
+                "./g.js"
+            ]
+        );
+    }
+
+    #[test]
+    fn js_skips_comments_strings_and_regexes() {
+        let src = r#"// import "./no.js";
+/* import "./no.js"; */
+const s = 'import "./no.js"';
+const t = `import "./no.js" ${ {a: "}"}["a"] } import("./no.js")`;
+const r = /import "\.\/no\.js"['"]/g;
+const q = a / 2 / b;
+obj.import("./no.js");
+export const x = 1;
+import "./yes.js";
+"#;
+        assert_eq!(urls(src, js_refs(src.as_bytes())), ["./yes.js"]);
+    }
+
+    #[test]
+    fn resolve_relative() {
+        let resolve = |base, url| resolve(base, url, Lang::Css);
+        let target = |key: &str, suffix: &str| {
+            Some((String::from(key), String::from(suffix)))
+        };
+
+        assert_eq!(resolve("css/app.css", "a.png"), target("css/a.png", ""));
+        assert_eq!(resolve("css/app.css", "./a.png"), target("css/a.png", ""));
+        assert_eq!(
+            resolve("css/app.css", "../img/a.png"),
+            target("img/a.png", "")
+        );
+        assert_eq!(
+            resolve("css/app.css", "/img/a.png"),
+            target("img/a.png", "")
+        );
+        assert_eq!(
+            resolve("css/app.css", "../a.woff2?v=1#iefix"),
+            target("a.woff2", "?v=1#iefix")
+        );
+        assert_eq!(resolve("index.html", "a.css#x"), target("a.css", "#x"));
+        assert_eq!(resolve("a/b/c.css", "../../d.png"), target("d.png", ""));
+        assert_eq!(resolve("a/b.css", "../../c.png"), None);
+    }
+
+    #[test]
+    fn resolve_skips_external() {
+        for url in [
+            "",
+            "#top",
+            "//cdn.example.com/a.js",
+            "https://example.com/a.js",
+            "data:image/png;base64,AAAA",
+            "mailto:a@example.com",
+        ] {
+            assert_eq!(resolve("index.html", url, Lang::Html), None);
+        }
+        assert_eq!(resolve("app.js", "lodash", Lang::Js), None);
+        assert_eq!(resolve("app.js", "a.js", Lang::Js), None);
+        assert_eq!(
+            resolve("js/app.js", "../lib/a.js", Lang::Js),
+            Some((String::from("lib/a.js"), String::new()))
+        );
+    }
+
+    #[test]
+    fn locate_keeps_style() {
+        let locate = |base, url, target, suffix| {
+            locate(base, false, url, target, suffix)
+        };
+        assert_eq!(locate("app.css", "a.png", "a.1.png", ""), "a.1.png");
+        assert_eq!(locate("app.css", "./a.png", "a.1.png", ""), "./a.1.png");
+        assert_eq!(
+            locate("css/app.css", "../img/a.png", "img/a.1.png", "?v=1"),
+            "../img/a.1.png?v=1"
+        );
+        assert_eq!(
+            locate("js/app.js", "../js/util.js", "js/util.1.js", ""),
+            "./util.1.js"
+        );
+        assert_eq!(
+            locate("css/app.css", "/img/a.png", "img/a.1.png", "#x"),
+            "/img/a.1.png#x"
+        );
+        assert_eq!(
+            locate("css/1/app.css", "../a.png", "css/a.png", ""),
+            "../a.png"
+        );
+    }
+
+    #[test]
+    fn locate_never_shares_hashed_dir() {
+        assert_eq!(
+            locate("css/1/app.css", true, "a.png", "css/1/a.png", ""),
+            "../1/a.png"
+        );
+        assert_eq!(
+            locate("css/1/app.css", false, "a.png", "css/1/a.png", ""),
+            "a.png"
+        );
+    }
+
+    #[test]
+    fn rewrites_references() {
+        let out = run(&[
+            ("index.html", r#"<script src="js/app.js?v=1"></script>"#),
+            ("js/app.js", "import './util.js';"),
+            ("js/util.js", "export const x = 1;"),
+        ]);
+        assert_eq!(out.keys["index.html"], "index.html");
+
+        let util = &out.keys["js/util.js"];
+        let app = &out.keys["js/app.js"];
+        assert_ne!(util, "js/util.js");
+        assert_eq!(
+            contents(&out, "js/app.js"),
+            format!("import './{}';", &util["js/".len()..])
+        );
+        assert_eq!(
+            contents(&out, "index.html"),
+            format!(r#"<script src="{app}?v=1"></script>"#)
+        );
+    }
+
+    #[test]
+    fn fingerprints_cycles_as_a_group() {
+        let assets = [
+            ("a.js", "import './b.js'; export const a = 1;"),
+            ("b.js", "import { a } from './a.js';"),
+            ("c.js", "import './c.js';"),
+        ];
+        let out = run(&assets);
+
+        let (a, b) = (&out.keys["a.js"], &out.keys["b.js"]);
+        assert_ne!(a, "a.js");
+        assert_ne!(b, "b.js");
+        assert_eq!(
+            contents(&out, "a.js"),
+            format!("import './{b}'; export const a = 1;")
+        );
+        assert_eq!(
+            contents(&out, "b.js"),
+            format!("import {{ a }} from './{a}';")
+        );
+
+        let c = &out.keys["c.js"];
+        assert_ne!(c, "c.js");
+        assert_eq!(contents(&out, "c.js"), format!("import './{c}';"));
+
+        // A change to one asset of the cycle changes the keys of both.
+        let changed =
+            run(&[("a.js", "import './b.js'; export const a = 2;"), assets[1]]);
+        assert_ne!(&changed.keys["a.js"], a);
+        assert_ne!(&changed.keys["b.js"], b);
+    }
+
+    #[test]
+    fn css_references() {
+        let src = br#"@import "base.css";
+.a { background: url(img/a.png?v=1) }
+.b { background: url("data:image/png;base64,AAAA") }
+.c { background: url(img/a.png?v=1) }"#;
+        let (code, refs) = css_refs(src).unwrap();
+        let urls: Vec<_> = refs.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "base.css",
+                "img/a.png?v=1",
+                "data:image/png;base64,AAAA",
+                "img/a.png?v=1"
+            ]
+        );
+        for r in &refs {
+            assert_ne!(code[r.range.clone()], *r.url.as_bytes());
+        }
+
+        let out = run(&[
+            ("css/app.css", std::str::from_utf8(src).unwrap()),
+            ("css/img/a.png", "png"),
+        ]);
+        let png = &out.keys["css/img/a.png"]["css/".len()..];
+        let css = contents(&out, "css/app.css");
+        assert!(css.contains(r#"@import "base.css""#), "{css}");
+        assert!(css.contains(&format!(r#"url("{png}?v=1")"#)), "{css}");
+        assert!(css.contains("data:image/png;base64,AAAA"), "{css}");
+        assert!(!css.contains("img/a.png"), "{css}");
+    }
+
+    #[test]
+    fn html_inline_styles_and_scripts() {
+        let html = r#"<style>.a { background: url(img/a.png) }</style>
+<div style="background: url(&quot;img/a.png&quot;)"></div>
+<div style='color: red'></div>
+<script type="module">import "./js/app.js";</script>
+<script>import("./js/app.js")</script>"#;
+        let out = run(&[
+            ("index.html", html),
+            ("img/a.png", "png"),
+            ("js/app.js", "export {};"),
+        ]);
+        let (png, app) = (&out.keys["img/a.png"], &out.keys["js/app.js"]);
+        let html = contents(&out, "index.html");
+        assert!(html.contains(&format!(r#"url("{png}")"#)), "{html}");
+        assert!(
+            html.contains(&format!(
+                r#"<div style="background: url(&quot;{png}&quot;)"></div>"#
+            )),
+            "{html}"
+        );
+        assert!(html.contains("<div style='color: red'></div>"), "{html}");
+        assert!(
+            html.contains(&format!(
+                r#"<script type="module">import "./{app}";</script>"#
+            )),
+            "{html}"
+        );
+        assert!(
+            html.contains(&format!(r#"<script>import("./{app}")</script>"#)),
+            "{html}"
+        );
+        assert!(!html.contains("img/a.png"), "{html}");
+
+        let invalid = [("index.html", "<div style=\"color: {\"></div>")];
+        let mut set = AssetSet::new();
+        for (key, contents) in invalid {
+            set.insert(Asset::new_buf(String::from(key), contents.as_bytes()));
+        }
+        assert!(Rewrite::new().run(set).is_err());
+    }
+
+    #[test]
+    fn decodes_attributes() {
+        assert_eq!(decode_attr("a &amp; b"), "a & b");
+        assert_eq!(decode_attr("&quot;&#39;&#x3C;&lt;"), "\"'<<");
+        assert_eq!(decode_attr("&nbsp;&;&#xZZ;&"), "&nbsp;&;&#xZZ;&");
+    }
+
+    #[test]
+    fn directory_placement() {
+        let mut set = AssetSet::new();
+        for (key, contents) in [
+            ("css/app.css", ".a { background: url(a.png) }"),
+            // An empty asset gets the hash of the stand-in key.
+            ("css/a.png", ""),
+            ("js/app.js", "import './util.js';"),
+            ("js/util.js", "export const x = 1;"),
+        ] {
+            set.insert(Asset::new_buf(String::from(key), contents.as_bytes()));
+        }
+        let fingerprint =
+            Fingerprint::new().placement(avenue::Placement::Directory);
+        let out = Rewrite::new()
+            .fingerprint(fingerprint.clone())
+            .run(set)
+            .unwrap();
+
+        let png = &out.keys["css/a.png"];
+        let app = &out.keys["css/app.css"];
+        assert_eq!(png, &fingerprint.key("css/a.png", b""));
+        assert_eq!(app.split('/').count(), 3);
+        assert_ne!(parent(app), parent(png));
+        let css = contents(&out, "css/app.css");
+        assert!(
+            css.contains(&format!(r#"url("../{}")"#, &png[4..])),
+            "{css}"
+        );
+
+        let util = &out.keys["js/util.js"];
+        assert_eq!(
+            contents(&out, "js/app.js"),
+            format!("import '../{}';", &util["js/".len()..])
+        );
+    }
+
+    #[cfg(feature = "manifest")]
+    #[test]
+    fn manifest() {
+        use avenue::ContentEncoding;
+
+        let out = run(&[
+            ("index.html", r#"<link rel=stylesheet href="app.css">"#),
+            ("app.css", "body{}"),
+        ]);
+        let manifest = out
+            .manifest(|entry| match entry.mime {
+                Some(ref mime) if *mime == mime::TEXT_CSS => {
+                    vec![ContentEncoding::Brotli, ContentEncoding::Gzip]
+                }
+                _ => Vec::new(),
+            })
+            .unwrap();
+
+        let css = manifest.get("app.css").unwrap();
+        assert_eq!(css.file, out.keys["app.css"]);
+        assert_eq!(css.mime, Some(mime::TEXT_CSS));
+        assert_eq!(
+            css.encodings,
+            [ContentEncoding::Brotli, ContentEncoding::Gzip]
+        );
+        let html = manifest.get("index.html").unwrap();
+        assert_eq!(html.file, "index.html");
+        assert!(html.encodings.is_empty());
+        assert_eq!(
+            html.digest,
+            avenue::Digest::sha256(contents(&out, "index.html").as_bytes())
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use std::{fmt, str::FromStr};
+
+#[cfg(any(
+    feature = "brotli",
+    feature = "flate2",
+    feature = "zstd",
+    feature = "minify-js",
+    feature = "lightningcss",
+    feature = "minify-html"
+))]
+use avenue::Process;
+use avenue::{BufAsset, Error, Stage};
+
+#[cfg(any(
+    feature = "brotli",
+    feature = "flate2",
+    feature = "zstd",
+    feature = "minify-js",
+    feature = "lightningcss",
+    feature = "minify-html"
+))]
+use crate::Builder;
+
+/// The [`Builder`] adapters as runtime [`Stage`]s.
+///
+/// Stages can be parsed from the name of the corresponding [`Builder`]
+/// method, e.g. `"minify_css"` or `"compress_brotli"`, so pipelines can be
+/// assembled from configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WebStage {
+    #[cfg(feature = "brotli")]
+    CompressBrotli,
+    #[cfg(feature = "flate2")]
+    CompressDeflate,
+    #[cfg(feature = "flate2")]
+    CompressGzip,
+    #[cfg(feature = "zstd")]
+    CompressZstd,
+    #[cfg(feature = "minify-js")]
+    MinifyJs,
+    #[cfg(feature = "lightningcss")]
+    MinifyCss,
+    #[cfg(feature = "minify-html")]
+    MinifyHtml,
+    /// Minifies based on the mime type of the key, see
+    /// [`Builder::minify_or_fallback`].
+    #[cfg(any(
+        feature = "minify-js",
+        feature = "lightningcss",
+        feature = "minify-html"
+    ))]
+    Minify,
+}
+
+impl WebStage {
+    /// Returns the name of this stage, which matches the [`Builder`] method.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            #[cfg(feature = "brotli")]
+            WebStage::CompressBrotli => "compress_brotli",
+            #[cfg(feature = "flate2")]
+            WebStage::CompressDeflate => "compress_deflate",
+            #[cfg(feature = "flate2")]
+            WebStage::CompressGzip => "compress_gzip",
+            #[cfg(feature = "zstd")]
+            WebStage::CompressZstd => "compress_zstd",
+            #[cfg(feature = "minify-js")]
+            WebStage::MinifyJs => "minify_js",
+            #[cfg(feature = "lightningcss")]
+            WebStage::MinifyCss => "minify_css",
+            #[cfg(feature = "minify-html")]
+            WebStage::MinifyHtml => "minify_html",
+            #[cfg(any(
+                feature = "minify-js",
+                feature = "lightningcss",
+                feature = "minify-html"
+            ))]
+            WebStage::Minify => "minify",
+        }
+    }
+}
+
+impl fmt::Display for WebStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebStage {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            #[cfg(feature = "brotli")]
+            "compress_brotli" => Ok(WebStage::CompressBrotli),
+            #[cfg(feature = "flate2")]
+            "compress_deflate" => Ok(WebStage::CompressDeflate),
+            #[cfg(feature = "flate2")]
+            "compress_gzip" => Ok(WebStage::CompressGzip),
+            #[cfg(feature = "zstd")]
+            "compress_zstd" => Ok(WebStage::CompressZstd),
+            #[cfg(feature = "minify-js")]
+            "minify_js" => Ok(WebStage::MinifyJs),
+            #[cfg(feature = "lightningcss")]
+            "minify_css" => Ok(WebStage::MinifyCss),
+            #[cfg(feature = "minify-html")]
+            "minify_html" => Ok(WebStage::MinifyHtml),
+            #[cfg(any(
+                feature = "minify-js",
+                feature = "lightningcss",
+                feature = "minify-html"
+            ))]
+            "minify" => Ok(WebStage::Minify),
+            _ => Err(Error::other(format!("unknown stage `{s}`"))),
+        }
+    }
+}
+
+impl Stage for WebStage {
+    #[inline]
+    fn name(&self) -> &str {
+        self.as_str()
+    }
+
+    /// Includes the version of `avenue-web`, since encoders and minifiers
+    /// may produce different output after an upgrade.
+    fn fingerprint(&self) -> String {
+        format!("{}@{}", self.as_str(), env!("CARGO_PKG_VERSION"))
+    }
+
+    fn apply(&self, key: &str, contents: Vec<u8>) -> Result<Vec<u8>, Error> {
+        // Unused when no adapter features are enabled.
+        #[allow(unused_variables)]
+        let asset = BufAsset::new(key, contents);
+        match *self {
+            #[cfg(feature = "brotli")]
+            WebStage::CompressBrotli => asset.compress_brotli().process_full(),
+            #[cfg(feature = "flate2")]
+            WebStage::CompressDeflate => {
+                asset.compress_deflate().process_full()
+            }
+            #[cfg(feature = "flate2")]
+            WebStage::CompressGzip => asset.compress_gzip().process_full(),
+            #[cfg(feature = "zstd")]
+            WebStage::CompressZstd => asset.compress_zstd().process_full(),
+            #[cfg(feature = "minify-js")]
+            WebStage::MinifyJs => asset.minify_js().process_full(),
+            #[cfg(feature = "lightningcss")]
+            WebStage::MinifyCss => asset.minify_css().process_full(),
+            #[cfg(feature = "minify-html")]
+            WebStage::MinifyHtml => asset.minify_html().process_full(),
+            #[cfg(any(
+                feature = "minify-js",
+                feature = "lightningcss",
+                feature = "minify-html"
+            ))]
+            WebStage::Minify => asset
+                .minify_or_fallback()
+                .process_full()
+                .map(|out| AsRef::<[u8]>::as_ref(&out).to_vec()),
+        }
+    }
+}